@@ -19,6 +19,38 @@ struct Opts {
     /// Action to perform; default is to generate SVG.
     #[arg(short, long, value_enum)]
     action: Option<Action>,
+
+    /// Tempo in beats per minute, for animated SVG.
+    #[arg(short, long, default_value_t = skreate::DEFAULT_TEMPO)]
+    tempo: u32,
+
+    /// Width of PNG output in pixels.
+    #[arg(short, long, conflicts_with = "dpi")]
+    width: Option<u32>,
+
+    /// Resolution of PNG output, where 96 DPI gives one pixel per centimetre.
+    #[arg(short, long)]
+    dpi: Option<u32>,
+
+    /// Page size for PDF output.
+    #[arg(short, long, value_enum, default_value_t = Page::A4)]
+    page: Page,
+
+    /// Scale for PDF output, as N for 1:N; fit to page if not specified.
+    #[arg(short, long)]
+    scale: Option<u32>,
+
+    /// Scale of values file for base value calculation; uses the bundled values if not specified.
+    #[arg(long)]
+    sov: Option<String>,
+
+    /// Competition segment whose rules apply when checking jump elements.
+    #[arg(short, long, value_enum, default_value_t = Rules::SeniorFree)]
+    rules: Rules,
+
+    /// Assume that body movements are used, when estimating step sequence level.
+    #[arg(long)]
+    body_movements: bool,
 }
 
 #[derive(Clone, Copy, Default, Debug, ValueEnum)]
@@ -26,12 +58,72 @@ enum Action {
     /// Generate SVG file.
     #[default]
     Generate,
+    /// Generate animated SVG file.
+    Animate,
+    /// Generate PNG file.
+    Png,
+    /// Generate PDF file.
+    Pdf,
     /// Convert to minimal form.
     Minimize,
     /// Expand all parameters fully.
     Expand,
     /// Convert parameters to canonical form.
     Canonicalize,
+    /// Check for questionable input, including impossible changes of foot, direction or edge.
+    Lint,
+    /// Calculate the base value of each element.
+    Score,
+    /// Check the jump elements against the well-balanced program rules.
+    Check,
+    /// Analyse turns and steps, and estimate the step sequence level.
+    Steps,
+}
+
+#[derive(Clone, Copy, Default, Debug, ValueEnum)]
+enum Rules {
+    /// Senior short program.
+    SeniorShort,
+    /// Senior free skating.
+    #[default]
+    SeniorFree,
+    /// Junior short program.
+    JuniorShort,
+    /// Junior free skating.
+    JuniorFree,
+}
+
+#[derive(Clone, Copy, Default, Debug, ValueEnum)]
+enum Page {
+    /// ISO A4.
+    #[default]
+    A4,
+    /// US Letter.
+    Letter,
+}
+
+/// Report all of the errors on stderr, and return an overall error.
+fn report(errs: Vec<skreate::ParseError>) -> anyhow::Error {
+    for err in &errs {
+        eprintln!("{err}");
+    }
+    anyhow::anyhow!("found {} error(s) in input", errs.len())
+}
+
+/// Report any errors from generating output on stderr, and return an overall error.
+fn report_output(err: skreate::OutputError) -> anyhow::Error {
+    match err {
+        skreate::OutputError::Parse(errs) => report(errs),
+        err @ skreate::OutputError::Render(_) => err.into(),
+    }
+}
+
+/// Report any warnings for the diagram on stderr, and return the generated output.
+fn warned<T>((output, warnings): (T, Vec<skreate::Warning>)) -> T {
+    for warning in &warnings {
+        eprintln!("{warning}");
+    }
+    output
 }
 
 fn main() -> Result<()> {
@@ -47,18 +139,95 @@ fn main() -> Result<()> {
     let mut input = String::new();
     reader.read_to_string(&mut input)?;
 
+    // Included files are found relative to the input file.
+    let dir = match opts.infile.as_ref() {
+        "-" => std::path::Path::new("."),
+        f => std::path::Path::new(f)
+            .parent()
+            .unwrap_or(std::path::Path::new(".")),
+    };
+    let resolver = skreate::FileResolver::new(dir);
+
     let action = opts.action.unwrap_or_default();
     let output = match action {
-        Action::Generate => skreate::generate(&input)?,
-        Action::Minimize => skreate::minimize(&input)?,
-        Action::Expand => skreate::expand(&input)?,
-        Action::Canonicalize => skreate::canonicalize(&input)?,
+        Action::Generate => {
+            warned(skreate::generate_with_warnings(&input, &resolver).map_err(report)?).into_bytes()
+        }
+        Action::Animate => warned(
+            skreate::generate_animated_with_warnings(&input, &resolver, opts.tempo)
+                .map_err(report)?,
+        )
+        .into_bytes(),
+        Action::Png => {
+            let size = match (opts.width, opts.dpi) {
+                (Some(width), _) => skreate::PngSize::Width(width),
+                (None, Some(dpi)) => skreate::PngSize::Dpi(dpi),
+                (None, None) => skreate::PngSize::default(),
+            };
+            warned(
+                skreate::generate_png_with_warnings(&input, &resolver, size)
+                    .map_err(report_output)?,
+            )
+        }
+        Action::Pdf => {
+            let pdf_opts = skreate::PdfOptions {
+                page: match opts.page {
+                    Page::A4 => skreate::PageSize::A4,
+                    Page::Letter => skreate::PageSize::Letter,
+                },
+                scale: match opts.scale {
+                    Some(n) => skreate::PdfScale::Ratio(n),
+                    None => skreate::PdfScale::Fit,
+                },
+            };
+            warned(
+                skreate::generate_pdf_with_warnings(&input, &resolver, pdf_opts)
+                    .map_err(report_output)?,
+            )
+        }
+        Action::Minimize => skreate::minimize(&input)?.into_bytes(),
+        Action::Expand => skreate::expand(&input)?.into_bytes(),
+        Action::Canonicalize => skreate::canonicalize(&input)?.into_bytes(),
+        Action::Lint => skreate::lint(&input, &resolver)
+            .map_err(report)?
+            .iter()
+            .map(|warning| format!("{warning}\n"))
+            .collect::<String>()
+            .into_bytes(),
+        Action::Score => {
+            let sov = match opts.sov {
+                Some(f) => skreate::ScaleOfValues::parse(&std::fs::read_to_string(f)?)?,
+                None => skreate::ScaleOfValues::default(),
+            };
+            skreate::score(&input, &resolver, &sov)
+                .map_err(report)?
+                .to_string()
+                .into_bytes()
+        }
+        Action::Steps => skreate::analyze_steps(&input, &resolver, opts.body_movements)
+            .map_err(report)?
+            .to_string()
+            .into_bytes(),
+        Action::Check => {
+            let rules = match opts.rules {
+                Rules::SeniorShort => skreate::RuleSet::SeniorShort,
+                Rules::SeniorFree => skreate::RuleSet::SeniorFree,
+                Rules::JuniorShort => skreate::RuleSet::JuniorShort,
+                Rules::JuniorFree => skreate::RuleSet::JuniorFree,
+            };
+            skreate::check_rules(&input, &resolver, rules)
+                .map_err(report)?
+                .iter()
+                .map(|warning| format!("{warning}\n"))
+                .collect::<String>()
+                .into_bytes()
+        }
     };
 
     let mut writer: Box<dyn std::io::Write> = match opts.outfile {
         None => Box::new(std::io::stdout()),
         Some(f) => Box::new(std::fs::File::create(f)?),
     };
-    writer.write_all(&output.into_bytes())?;
+    writer.write_all(&output)?;
     Ok(())
 }
@@ -8,5 +8,5 @@ fuzz_target!(|data: &[u8]| {
     if !input.is_ascii() {
         return;
     }
-    let _result = skreate::generate_with_positions(input);
+    let _result = skreate::generate_with_positions(input, &skreate::MemoryResolver::default());
 });
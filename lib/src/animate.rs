@@ -0,0 +1,122 @@
+// Copyright 2025 David Drysdale
+
+//! Animation of skater tracks using SMIL.
+
+use crate::{path, Move, Position, RenderOptions, Skater};
+use log::debug;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use svg::node::element::{AnimateMotion, Circle, Group};
+use svg::Document;
+
+/// Default tempo, in beats per minute.
+pub const DEFAULT_TEMPO: u32 = 120;
+
+/// Motion of a skater during a single move.
+#[derive(Debug)]
+struct Motion {
+    /// Points along the path, in diagram coordinates.
+    points: Vec<Position>,
+    /// Number of beats into the skater's timeline that the move starts.
+    start: usize,
+    /// Number of beats that the move takes.
+    beats: usize,
+}
+
+/// Motion of a single skater.
+#[derive(Debug, Default)]
+struct Timeline {
+    colour: String,
+    motions: Vec<Motion>,
+    /// Total number of beats so far.
+    beats: usize,
+}
+
+/// Accumulated motion of all skaters, for rendering as animations.
+#[derive(Debug)]
+pub(crate) struct Animation {
+    /// Tempo in beats per minute.
+    tempo: u32,
+    /// Timelines for each skater, indexed by name.
+    timelines: BTreeMap<String, Timeline>,
+}
+
+impl Animation {
+    /// Create an animation that runs at the given tempo.
+    pub fn new(tempo: u32) -> Self {
+        Self {
+            tempo: std::cmp::max(tempo, 1),
+            timelines: BTreeMap::new(),
+        }
+    }
+
+    /// Add the motion for a move of the given duration in beats, performed by the named skater from `start`.
+    pub fn add(&mut self, name: &str, start: &Skater, mv: &dyn Move, beats: usize, colour: &str) {
+        let timeline = self.timelines.entry(name.to_string()).or_default();
+        timeline.colour = colour.to_string();
+        let mut points = mv
+            .path_points()
+            .into_iter()
+            .map(|pt| start.pos.add_rotated(start.dir, pt))
+            .collect::<Vec<_>>();
+        points.dedup();
+        if beats > 0 && points.len() > 1 {
+            timeline.motions.push(Motion {
+                points,
+                start: timeline.beats,
+                beats,
+            });
+        }
+        timeline.beats += beats;
+    }
+
+    /// Convert a number of beats to seconds.
+    fn seconds(&self, beats: usize) -> f64 {
+        beats as f64 * 60.0 / self.tempo as f64
+    }
+
+    /// Render a moving marker for each skater into the document.
+    pub fn render(&self, mut doc: Document, opts: &RenderOptions) -> Document {
+        let size = 10 * opts.stroke_width().0 as i64;
+        for (name, timeline) in &self.timelines {
+            let Some(first) = timeline.motions.first() else {
+                continue;
+            };
+            let start = first.points[0];
+            // Marker points along the x-axis, which `rotate="auto"` aligns with the direction of motion.
+            let mut marker = Group::new()
+                .set(
+                    "style",
+                    format!(
+                        "stroke:{0}; fill:{0}; stroke-width:{1};",
+                        timeline.colour,
+                        opts.stroke_width().0
+                    ),
+                )
+                .set("transform", format!("translate({} {})", start.x, start.y))
+                .add(Circle::new().set("r", size))
+                .add(path!("M 0,0 l {},0", 2 * size));
+            debug!(
+                "animate {} moves for skater {name:?}",
+                timeline.motions.len()
+            );
+            for motion in &timeline.motions {
+                let mut d = String::new();
+                for (idx, pt) in motion.points.iter().enumerate() {
+                    let cmd = if idx == 0 { "M" } else { "L" };
+                    let _ = write!(d, "{cmd} {},{} ", pt.x.0 - start.x.0, pt.y.0 - start.y.0);
+                }
+                marker = marker.add(
+                    AnimateMotion::new()
+                        .set("path", d.trim_end())
+                        .set("begin", format!("{:.3}s", self.seconds(motion.start)))
+                        .set("dur", format!("{:.3}s", self.seconds(motion.beats)))
+                        .set("rotate", "auto")
+                        .set("fill", "freeze"),
+                );
+            }
+            doc = doc.add(marker);
+        }
+        doc
+    }
+}
@@ -1,6 +1,6 @@
 // Copyright 2024-2025 David Drysdale
 
-//! Error type.
+//! Error types.
 
 use crate::TextPosition;
 use std::fmt::{self, Display, Formatter};
@@ -12,20 +12,97 @@ pub struct ParseError {
     pub pos: TextPosition,
     /// Error information.
     pub msg: String,
+    /// Name of the included file that the position refers to; [`None`] for the main input.
+    pub file: Option<String>,
+    /// Invocations of named sequences that the position was reached through, innermost first.
+    pub calls: Vec<CallSite>,
+}
+
+impl ParseError {
+    /// Mark the error as occurring in the given included file, unless it already refers to a (nested) file.
+    pub(crate) fn in_file(mut self, file: &str) -> Self {
+        if self.file.is_none() {
+            self.file = Some(file.to_string());
+        }
+        self
+    }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{}: {}", self.pos.row + 1, self.pos.col + 1, self.msg)
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        write!(f, "{}:{}: {}", self.pos.row + 1, self.pos.col + 1, self.msg)?;
+        for call in &self.calls {
+            write!(f, " ({call})")?;
+        }
+        Ok(())
     }
 }
 impl std::error::Error for ParseError {}
 
+/// Invocation of a named sequence, for a move that was expanded from the sequence's definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    /// Name of the sequence.
+    pub name: String,
+    /// Position of the `Call` of the sequence.
+    pub pos: TextPosition,
+    /// Name of the included file that holds the `Call`; [`None`] for the main input.
+    pub file: Option<String>,
+}
+
+impl Display for CallSite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "in \"{}\" invoked at ", self.name)?;
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        write!(f, "{}:{}", self.pos.row + 1, self.pos.col + 1)
+    }
+}
+
+/// Error in generating rendered output (such as PNG or PDF) from input.
+#[derive(Debug, Clone)]
+pub enum OutputError {
+    /// Errors in the input, in text order.
+    Parse(Vec<ParseError>),
+    /// Failure to render or encode the output, which has no position in the input.
+    Render(String),
+}
+
+impl Display for OutputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputError::Parse(errs) => {
+                let errs = errs.iter().map(|err| err.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", errs.join("\n"))
+            }
+            OutputError::Render(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+impl std::error::Error for OutputError {}
+
+impl From<ParseError> for OutputError {
+    fn from(err: ParseError) -> OutputError {
+        OutputError::Parse(vec![err])
+    }
+}
+impl From<Vec<ParseError>> for OutputError {
+    fn from(errs: Vec<ParseError>) -> OutputError {
+        OutputError::Parse(errs)
+    }
+}
+
 impl From<std::io::Error> for ParseError {
     fn from(err: std::io::Error) -> ParseError {
         ParseError {
             pos: TextPosition::default(),
             msg: format!("{err}"),
+            file: None,
+            calls: Vec::new(),
         }
     }
 }
@@ -34,6 +111,8 @@ impl From<std::string::FromUtf8Error> for ParseError {
         ParseError {
             pos: TextPosition::default(),
             msg: format!("{err}"),
+            file: None,
+            calls: Vec::new(),
         }
     }
 }
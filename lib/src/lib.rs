@@ -3,11 +3,21 @@
 //! Skating diagram creator.
 #![warn(missing_docs)]
 
-pub use crate::error::ParseError;
+pub use crate::animate::DEFAULT_TEMPO;
+pub use crate::error::{CallSite, OutputError, ParseError};
 use crate::moves::{MoveId, PseudoMoveId};
 pub use crate::params::MoveParam;
+#[cfg(feature = "pdf")]
+pub use crate::pdf::{generate_pdf, generate_pdf_with_warnings, PageSize, PdfOptions, PdfScale};
+#[cfg(feature = "png")]
+pub use crate::raster::{generate_png, generate_png_with_warnings, svg_to_png, PngSize};
+pub use crate::resolver::{FileResolver, MemoryResolver, Resolver};
+pub use crate::rules::RuleSet;
+pub use crate::scoring::{element_code, Points, ScaleOfValues, ScoreSheet, ScoredElement};
+pub use crate::steps::{StepAnalysis, Turn, TurnType, Variety};
 pub use crate::types::*;
-use log::{debug, error, info, trace};
+pub use crate::warning::{Warning, WarningKind};
+use log::{debug, info, trace, warn};
 use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug, Display, Formatter};
 use svg::{
@@ -16,11 +26,21 @@ use svg::{
     Document,
 };
 
+mod animate;
 mod error;
 pub mod moves;
 pub mod params;
 pub mod parser;
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "png")]
+mod raster;
+mod resolver;
+mod rules;
+mod scoring;
+mod steps;
 mod types;
+mod warning;
 
 /// Extra margin to put around calculated bounding box.
 const MARGIN: Centimetres = cm!(50);
@@ -162,14 +182,16 @@ struct RenderOptions {
     show_bounds: bool,
     /// Whether to show bounds of individual moves.
     show_move_bounds: bool,
+    /// Whether to check that each move can physically follow on from the previous move.
+    strict: bool,
     /// Calculated bounds.
     bounds: Bounds,
     /// Font size.
     font_size: MainFontSize,
     /// Stroke width; auto-scale with bounds if [`None`].
     stroke_width: Option<StrokeWidth>,
-    /// Next unique ID associated with a particular [`TextPosition`].
-    next_for_pos: HashMap<TextPosition, usize>,
+    /// Next unique ID associated with a particular [`TextPosition`] (in a particular file).
+    next_for_pos: HashMap<(Option<String>, TextPosition), usize>,
     /// Label offset scaling percentage.
     label_offset: Percentage,
 
@@ -180,12 +202,39 @@ struct RenderOptions {
     count: Option<Count>,
     /// Duration of current move.
     duration: Option<Duration>,
+
+    /// Stroke colour for the current skater; black if [`None`].
+    colour: Option<String>,
+    /// Whether edge codes in labels should be switched to the opposite foot.
+    swap_feet: bool,
+    /// Name of the included file that the current move comes from; [`None`] for the main input.
+    file: Option<String>,
+    /// Rink for the current move, if the parts of the move that go outside it are drawn in a highlight colour.
+    highlight_rink: Option<moves::rink::Rink>,
 }
 
 impl RenderOptions {
+    /// Convert a position in the current file into an ID string.  Positions in included files are prefixed with the
+    /// file name, so that they cannot be confused with positions in the main input.
+    fn pos_id(&self, pos: TextPosition) -> String {
+        match &self.file {
+            Some(file) => {
+                let file: String = file
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                    .collect();
+                format!("f_{file}_{}", pos.unique_id())
+            }
+            None => pos.unique_id(),
+        }
+    }
+
     fn next_unique_id(&mut self, pos: TextPosition) -> String {
-        let pos_str = pos.unique_id();
-        let n = self.next_for_pos.entry(pos).or_insert(0);
+        let pos_str = self.pos_id(pos);
+        let n = self
+            .next_for_pos
+            .entry((self.file.clone(), pos))
+            .or_insert(0);
         *n += 1;
         if *n == 1 {
             pos_str
@@ -194,6 +243,16 @@ impl RenderOptions {
         }
     }
 
+    /// Return the highlight colour to draw a path in, if any of its `points` (relative to (0,0) at 0°) starting from
+    /// `start` are outside the rink.
+    fn highlight(&self, points: &[Position], start: &Skater) -> Option<String> {
+        self.highlight_rink
+            .as_ref()
+            .filter(|rink| rink.outside_path(points, start))
+            .and_then(|rink| rink.highlight())
+            .map(str::to_string)
+    }
+
     fn bounds_diag(&self) -> f64 {
         let diag_squared = self.bounds.width().0 * self.bounds.width().0
             + self.bounds.height().0 * self.bounds.height().0;
@@ -248,6 +307,100 @@ impl RenderOptions {
             width
         }
     }
+    /// Return the effective stroke colour.
+    pub fn colour(&self) -> &str {
+        self.colour.as_deref().unwrap_or("black")
+    }
+    /// Return the text for a label that may start with an edge code, switching the foot of the
+    /// code if required.
+    pub fn label_text(&self, text: String) -> String {
+        if !self.swap_feet {
+            return text;
+        }
+        match parser::types::parse_code(&text) {
+            Ok((rest, code)) => format!("{}{rest}", code.opposite()),
+            Err(_) => text,
+        }
+    }
+}
+
+/// State of an individual skater's track, for diagrams with multiple skaters.
+#[derive(Debug, Clone)]
+struct Track<'a> {
+    skater: Skater,
+    /// Whether the skater has yet to perform a move.
+    first: bool,
+    auto_count: Option<Count>,
+    colour: Option<String>,
+    /// Partner track generated alongside this skater's moves.
+    partner: Option<&'a moves::partner::Partner>,
+    /// Formation that repeats this skater's moves.
+    formation: Option<moves::formation::Anchored<'a>>,
+}
+
+impl Default for Track<'_> {
+    fn default() -> Self {
+        Self {
+            skater: Skater::at_zero(code!(BF)),
+            first: true,
+            auto_count: None,
+            colour: None,
+            partner: None,
+            formation: None,
+        }
+    }
+}
+
+/// Tracks of all skaters other than the currently active one, together with the state that all skaters share.
+#[derive(Debug, Default)]
+struct Tracks<'a> {
+    /// Name of the currently active skater.
+    current: String,
+    inactive: HashMap<String, Track<'a>>,
+    /// Rink that the moves of every skater are checked against.
+    rink: Option<&'a moves::rink::Rink>,
+}
+
+impl<'a> Tracks<'a> {
+    /// Apply any configuration held by `mv` to the `track` of the active skater, switching `track` over to a
+    /// different skater if `mv` selects one.
+    fn configure(&mut self, mv: &'a dyn Move, track: &mut Track<'a>) {
+        if let Some(config) = mv.as_partner() {
+            track.partner = Some(config).filter(|config| config.enabled);
+        }
+        if let Some(config) = mv.as_formation() {
+            track.formation = config.anchor(&track.skater);
+        }
+        if let Some(config) = mv.as_rink() {
+            self.rink = Some(config);
+        }
+        if let Some(select) = mv.as_skater() {
+            self.switch(select, track);
+        }
+    }
+
+    /// Return a copy of `mv` that is placed on the rink in force, if `mv` is a line on the rink.
+    fn place(&self, mv: &dyn Move) -> Option<Box<dyn Move>> {
+        let axis = mv.as_axis()?;
+        let rink = self.rink?;
+        Some(Box::new(axis.on(rink)))
+    }
+
+    /// Switch to the skater named by `select`, stashing the `track` of the active skater and replacing it with
+    /// the track of the newly active skater.  A new skater starts with no partner or formation.
+    fn switch(&mut self, select: &moves::skater::Skater, track: &mut Track<'a>) {
+        let new = Track {
+            auto_count: track.auto_count.map(|_| Count(1)),
+            ..Default::default()
+        };
+        let prev = std::mem::replace(&mut self.current, select.name.clone());
+        self.inactive.insert(prev, std::mem::take(track));
+        *track = self.inactive.remove(&select.name).unwrap_or(new);
+        if let Some(colour) = &select.colour {
+            track.colour = Some(colour.clone());
+        }
+        debug!("switch to skater {:?} at {}", self.current, track.skater);
+    }
 }
 
 fn use_at(skater: &Skater, def_id: &SvgId, opts: &RenderOptions) -> Use {
@@ -262,10 +415,19 @@ fn use_at(skater: &Skater, def_id: &SvgId, opts: &RenderOptions) -> Use {
         )
         .set(
             "style",
-            format!("stroke:black; stroke-width:{};", opts.stroke_width().0),
+            format!(
+                "stroke:{}; stroke-width:{};",
+                opts.colour(),
+                opts.stroke_width().0
+            ),
         )
 }
 
+/// Return `follower` if `mv` is a skating move, as only skating moves are repeated for other skaters.
+fn for_skating<T>(mv: &dyn Move, follower: Option<T>) -> Option<T> {
+    follower.filter(|_| matches!(mv.id(), MoveId::Skating(_)))
+}
+
 fn apply_style(path: Path, style: &str) -> Path {
     match style {
         "dashed" => path.set("stroke-dasharray", "50 30"),
@@ -290,6 +452,12 @@ struct TimedMove {
     count: Option<Count>,
     duration: Option<Duration>,
     mv: Box<dyn Move>,
+    /// Parameters that were specified as expressions, before evaluation.
+    exprs: Vec<MoveParam>,
+    /// Name of the included file that the move comes from; [`None`] for the main input.
+    file: Option<String>,
+    /// Invocations of named sequences that the move was expanded from, innermost first.
+    calls: Vec<CallSite>,
 }
 
 impl TimedMove {
@@ -307,14 +475,50 @@ impl TimedMove {
     fn text(&self) -> String {
         format!("{}{}", self.prefix(), self.mv.text())
     }
+    /// Text for the move in canonical form, but with any expressions preserved.
+    fn canonical_text(&self) -> String {
+        let text = self.with_exprs(self.mv.text(), params::to_string);
+        format!("{}{text}", self.prefix())
+    }
+    /// Text for the move with all parameters, but with any expressions preserved.
     fn expanded_text(&self) -> String {
-        format!("{}{}", self.prefix(), self.mv.expanded_text())
+        let text = self.with_exprs(self.mv.expanded_text(), params::to_expanded);
+        format!("{}{text}", self.prefix())
+    }
+    /// Replace the parameters at the end of `text` (as generated by `to_text`) with the equivalent parameters that
+    /// include the original expressions.
+    fn with_exprs(
+        &self,
+        text: String,
+        to_text: fn(&[params::Info], &[MoveParam]) -> String,
+    ) -> String {
+        if self.exprs.is_empty() {
+            return text;
+        }
+        let info = self.mv.id().info().params;
+        let mut params = self.mv.params();
+        if params.len() != info.len() {
+            return text;
+        }
+        let Some(base) = text.strip_suffix(&to_text(info, &params)) else {
+            warn!("failed to find parameters in '{text}', folding expressions");
+            return text;
+        };
+        for param in &mut params {
+            if let Some(expr) = self.exprs.iter().find(|expr| expr.name == param.name) {
+                param.value = expr.value.clone();
+            }
+        }
+        format!("{base}{}", to_text(info, &params))
     }
     fn opposite(&self, repeat: Option<usize>) -> Self {
         Self {
             count: self.count,
             duration: self.duration,
             mv: self.mv.opposite(repeat),
+            exprs: self.exprs.clone(),
+            file: self.file.clone(),
+            calls: self.calls.clone(),
         }
     }
     fn clone_at_repeat(&self, repeat: Option<usize>) -> Self {
@@ -322,6 +526,9 @@ impl TimedMove {
             count: self.count,
             duration: self.duration,
             mv: self.mv.box_clone(repeat),
+            exprs: self.exprs.clone(),
+            file: self.file.clone(),
+            calls: self.calls.clone(),
         }
     }
 }
@@ -332,6 +539,9 @@ impl From<Box<dyn Move>> for TimedMove {
             count: None,
             duration: None,
             mv,
+            exprs: Vec::new(),
+            file: None,
+            calls: Vec::new(),
         }
     }
 }
@@ -359,6 +569,17 @@ trait Move {
         Transition::default()
     }
 
+    /// Warning about the transition needed before starting the move from `from`, if any.  If `strict` is set, also
+    /// check that the move can physically follow on from `from`.
+    fn pre_transition_warning(&self, from: Code, strict: bool) -> Option<WarningKind> {
+        if strict {
+            self.start()
+                .and_then(|start| moves::continuity_warning(from, start))
+        } else {
+            None
+        }
+    }
+
     /// Transition as a result of the move, starting from `Direction(0)`, and assuming that [`pre_transition`] has
     /// already happened.
     fn transition(&self) -> Transition {
@@ -404,7 +625,13 @@ trait Move {
             Some(outer) => def_id.in_ns(outer),
             None => def_id,
         };
+        // Parts of the track that go outside the rink may be drawn in a highlight colour.
+        let highlight = opts.highlight(&self.path_points(), start);
+        let colour = highlight.map(|highlight| opts.colour.replace(highlight));
         let mut use_link = use_at(start, &def_id, opts);
+        if let Some(colour) = colour {
+            opts.colour = colour;
+        }
         if let Some(pos) = self.text_pos() {
             let unique_id = opts.next_unique_id(pos);
             use_link = use_link.set("id", unique_id);
@@ -433,7 +660,8 @@ trait Move {
                 .set(
                     "style",
                     format!(
-                        "stroke:black; fill:black; font-size:{}pt;",
+                        "stroke:{0}; fill:{0}; font-size:{1}pt;",
+                        opts.colour(),
                         opts.font_size().0
                     ),
                 );
@@ -454,6 +682,16 @@ trait Move {
     /// same `Move` (although it may have different `input_text`).
     fn expanded_text(&self) -> String;
 
+    /// Return points along the path of the move, relative to (0,0) at 0°.
+    ///
+    /// Default implementation assumes a straight line to the end of a relative transition.
+    fn path_points(&self) -> Vec<Position> {
+        match self.transition().spatial {
+            SpatialTransition::Relative { delta, rotate: _ } => vec![Position::default(), delta],
+            SpatialTransition::Absolute { .. } => Vec::new(),
+        }
+    }
+
     /// Return the position in the text that held the move, if available.
     fn text_pos(&self) -> Option<TextPosition> {
         None
@@ -470,6 +708,46 @@ trait Move {
     fn as_repeat_end(&self) -> Option<&moves::repeat::RepeatEnd> {
         None
     }
+
+    /// If the move starts a named sequence definition, return the underlying concrete type.
+    fn as_define(&self) -> Option<&moves::define::Define> {
+        None
+    }
+
+    /// If the move performs a named sequence, return the underlying concrete type.
+    fn as_call(&self) -> Option<&moves::define::Call> {
+        None
+    }
+
+    /// If the move includes another file, return the underlying concrete type.
+    fn as_include(&self) -> Option<&moves::include::Include> {
+        None
+    }
+
+    /// If the move switches skater, return the underlying concrete type.
+    fn as_skater(&self) -> Option<&moves::skater::Skater> {
+        None
+    }
+
+    /// If the move configures a partner track, return the underlying concrete type.
+    fn as_partner(&self) -> Option<&moves::partner::Partner> {
+        None
+    }
+
+    /// If the move configures a formation, return the underlying concrete type.
+    fn as_formation(&self) -> Option<&moves::formation::Formation> {
+        None
+    }
+
+    /// If the move describes a rink, return the underlying concrete type.
+    fn as_rink(&self) -> Option<&moves::rink::Rink> {
+        None
+    }
+
+    /// If the move is a line on the rink, return the underlying concrete type.
+    fn as_axis(&self) -> Option<&moves::axis::Axis> {
+        None
+    }
 }
 
 impl Debug for dyn Move {
@@ -483,28 +761,275 @@ impl Debug for dyn Move {
 
 /// Convert the input into a list of moves.
 fn moves(input: &str) -> Result<Vec<TimedMove>, ParseError> {
-    let (rest, move_inputs) = crate::parser::parse(input).map_err(|e| parser::err(e, input))?;
-    if !rest.is_empty() {
-        let pos = TextPosition::new(input, rest, rest);
-        error!("failed to parse remainder at {pos:?}: '{}'", rest);
-        Err(ParseError {
-            pos,
-            msg: "failed to parse".to_string(),
-        })
+    all_moves(input).map_err(first_error)
+}
+
+/// Convert the input into a list of moves, collecting all errors in the input (in text order) on failure.
+fn all_moves(input: &str) -> Result<Vec<TimedMove>, Vec<ParseError>> {
+    let (moves, errs) = partial_moves(input);
+    if errs.is_empty() {
+        Ok(moves)
+    } else {
+        Err(errs)
+    }
+}
+
+/// Convert the input into a list of moves, skipping over any text that fails to parse or any moves that fail to
+/// construct.  Returns the valid moves and all of the errors (in text order).
+fn partial_moves(input: &str) -> (Vec<TimedMove>, Vec<ParseError>) {
+    partial_moves_in(input, moves::Context::default())
+}
+
+/// Convert the input into a list of moves as for [`partial_moves`], starting from the given context.
+fn partial_moves_in(input: &str, mut ctx: moves::Context) -> (Vec<TimedMove>, Vec<ParseError>) {
+    let (move_inputs, mut errs) = crate::parser::parse(input);
+
+    // Convert the parsed move inputs into moves.
+    let mut moves = Vec::new();
+    for inputs in move_inputs {
+        match inputs.construct(&mut ctx) {
+            Ok(mv) => moves.push(mv),
+            Err(e) => errs.push(e),
+        }
+    }
+    errs.sort_by_key(|e| (e.pos.row, e.pos.col));
+    (moves, errs)
+}
+
+/// Return the first of a (non-empty) collection of errors.
+fn first_error(errs: Vec<ParseError>) -> ParseError {
+    errs.into_iter().next().expect("no errors")
+}
+
+/// Expand any included files, replacing each `Include` with the moves from the corresponding file.  The moves come
+/// from the main input if `file` is [`None`], and `active` holds the names of the files currently being included.
+/// Each included move records the file it came from, and files included from an included file are found relative to
+/// that file.  An included file starts with the variables defined before the `Include` that includes it.
+///
+/// On failure, returns all of the errors found, each marked with the file it was found in.
+fn expand_includes(
+    timed_mvs: Vec<TimedMove>,
+    resolver: &dyn Resolver,
+    file: Option<&str>,
+    active: &mut Vec<String>,
+) -> Result<Vec<TimedMove>, Vec<ParseError>> {
+    let mut expanded = Vec::new();
+    let mut errs = Vec::new();
+    for timed_mv in timed_mvs {
+        let Some(include) = timed_mv.mv.as_include() else {
+            expanded.push(timed_mv);
+            continue;
+        };
+        let err = |msg: String| ParseError {
+            pos: timed_mv.mv.text_pos().unwrap_or_default(),
+            msg,
+            file: file.map(|f| f.to_string()),
+            calls: Vec::new(),
+        };
+        let included_file = match file.and_then(|f| std::path::Path::new(f).parent()) {
+            Some(dir) => dir.join(&include.file).to_string_lossy().into_owned(),
+            None => include.file.clone(),
+        };
+        if active.contains(&included_file) {
+            errs.push(err(format!(
+                "file \"{included_file}\" is already being included!"
+            )));
+            continue;
+        }
+        let contents = match resolver.resolve(&included_file) {
+            Ok(contents) => contents,
+            Err(e) => {
+                errs.push(err(format!("failed to include \"{included_file}\": {e}")));
+                continue;
+            }
+        };
+        info!("include {} bytes from \"{included_file}\"", contents.len());
+        let ctx = moves::Context {
+            vars: include.vars.clone(),
+            ..Default::default()
+        };
+        let (mut included, included_errs) = partial_moves_in(&contents, ctx);
+        if !included_errs.is_empty() {
+            errs.extend(included_errs.into_iter().map(|e| e.in_file(&included_file)));
+            continue;
+        }
+        for included_mv in &mut included {
+            included_mv.file = Some(included_file.clone());
+        }
+        active.push(included_file.clone());
+        match expand_includes(included, resolver, Some(&included_file), active) {
+            Ok(included) => expanded.extend(included),
+            Err(included_errs) => errs.extend(included_errs),
+        }
+        active.pop();
+    }
+    if errs.is_empty() {
+        Ok(expanded)
     } else {
-        // Convert the parsed move inputs into moves.
-        let mut ctx = moves::Context::default();
-        let moves: Result<Vec<TimedMove>, ParseError> = move_inputs
-            .into_iter()
-            .map(|inputs| inputs.construct(&mut ctx))
-            .collect();
-        Ok(moves?)
+        Err(errs)
+    }
+}
+
+/// Expand any named sequences, removing their definitions and replacing each invocation with the moves from the
+/// corresponding definition.
+fn expand_definitions(timed_mvs: &[TimedMove]) -> Result<Vec<TimedMove>, ParseError> {
+    let mut defs: HashMap<&str, &[TimedMove]> = HashMap::new();
+    let mut idx = 0;
+    let mut expanded = Vec::new();
+    while idx < timed_mvs.len() {
+        let timed_mv = &timed_mvs[idx];
+        match timed_mv.mv.id() {
+            MoveId::Pseudo(PseudoMoveId::Define) => {
+                let define = timed_mv.mv.as_define().unwrap();
+                let pos = timed_mv.mv.text_pos().unwrap_or_default();
+                if defs.contains_key(define.name.as_str()) {
+                    return Err(ParseError {
+                        pos,
+                        msg: format!("sequence \"{}\" is already defined!", define.name),
+                        file: timed_mv.file.clone(),
+                        calls: timed_mv.calls.clone(),
+                    });
+                }
+                let start = idx + 1;
+                let mut repeat_depth = 0;
+                loop {
+                    idx += 1;
+                    let Some(inner) = timed_mvs.get(idx) else {
+                        return Err(ParseError {
+                            pos,
+                            msg: format!(
+                                "found end of input with definition of \"{}\" still pending!",
+                                define.name
+                            ),
+                            file: timed_mv.file.clone(),
+                            calls: timed_mv.calls.clone(),
+                        });
+                    };
+                    let inner_pos = inner.mv.text_pos().unwrap_or_default();
+                    match inner.mv.id() {
+                        MoveId::Pseudo(PseudoMoveId::DefineEnd) => break,
+                        MoveId::Pseudo(PseudoMoveId::Define) => {
+                            return Err(ParseError {
+                                pos: inner_pos,
+                                msg: format!("found definition inside definition of \"{}\"!", define.name),
+                                file: inner.file.clone(),
+                                calls: inner.calls.clone(),
+                            })
+                        }
+                        // Repeats have to be complete within a definition.
+                        MoveId::Pseudo(PseudoMoveId::RepeatStart) => repeat_depth += 1,
+                        MoveId::Pseudo(PseudoMoveId::RepeatEnd) if repeat_depth == 0 => {
+                            return Err(ParseError {
+                                pos: inner_pos,
+                                msg: format!(
+                                    "found end of repeat when no repeat in progress in definition of \"{}\"!",
+                                    define.name
+                                ),
+                                file: inner.file.clone(),
+                                calls: inner.calls.clone(),
+                            })
+                        }
+                        MoveId::Pseudo(PseudoMoveId::RepeatEnd) => repeat_depth -= 1,
+                        _ => {}
+                    }
+                }
+                if repeat_depth > 0 {
+                    return Err(ParseError {
+                        pos: timed_mvs[idx].mv.text_pos().unwrap_or_default(),
+                        msg: format!(
+                            "found end of definition of \"{}\" with repeat still pending!",
+                            define.name
+                        ),
+                        file: timed_mvs[idx].file.clone(),
+                        calls: timed_mvs[idx].calls.clone(),
+                    });
+                }
+                info!("[{start}..{idx}] define sequence \"{}\"", define.name);
+                defs.insert(&define.name, &timed_mvs[start..idx]);
+            }
+            MoveId::Pseudo(PseudoMoveId::DefineEnd) => {
+                return Err(ParseError {
+                    pos: timed_mv.mv.text_pos().unwrap_or_default(),
+                    msg: "found end of definition when no definition in progress!".to_string(),
+                    file: timed_mv.file.clone(),
+                    calls: timed_mv.calls.clone(),
+                });
+            }
+            MoveId::Pseudo(PseudoMoveId::Call) => {
+                expand_call(&defs, timed_mv, false, &mut Vec::new(), &mut expanded)?;
+            }
+            _ => expanded.push(timed_mv.clone_at_repeat(None)),
+        }
+        idx += 1;
+    }
+    Ok(expanded)
+}
+
+/// Append the moves for an invocation of a named sequence to `expanded`, mirrored if `flipped` is set.
+fn expand_call<'a>(
+    defs: &HashMap<&str, &'a [TimedMove]>,
+    timed_mv: &'a TimedMove,
+    flipped: bool,
+    active: &mut Vec<&'a str>,
+    expanded: &mut Vec<TimedMove>,
+) -> Result<(), ParseError> {
+    let call = timed_mv.mv.as_call().unwrap();
+    let pos = timed_mv.mv.text_pos().unwrap_or_default();
+    let Some(body) = defs.get(call.name.as_str()) else {
+        return Err(ParseError {
+            pos,
+            msg: format!("no definition found for sequence \"{}\"!", call.name),
+            file: timed_mv.file.clone(),
+            calls: timed_mv.calls.clone(),
+        });
+    };
+    if active.contains(&call.name.as_str()) {
+        return Err(ParseError {
+            pos,
+            msg: format!("sequence \"{}\" invokes itself!", call.name),
+            file: timed_mv.file.clone(),
+            calls: timed_mv.calls.clone(),
+        });
+    }
+    let flipped = flipped != call.opposite;
+    info!(
+        "expand sequence \"{}\" ({} moves){}",
+        call.name,
+        body.len(),
+        if flipped { " opposite" } else { "" }
+    );
+    active.push(&call.name);
+    let site = CallSite {
+        name: call.name.clone(),
+        pos,
+        file: timed_mv.file.clone(),
+    };
+    let start = expanded.len();
+    for inner in body.iter() {
+        if inner.mv.id() == MoveId::Pseudo(PseudoMoveId::Call) {
+            // Errors in a nested invocation are reported at their position in the definition, but also need to
+            // indicate where this sequence was invoked from.
+            expand_call(defs, inner, flipped, active, expanded).map_err(|mut e| {
+                e.calls.push(site.clone());
+                e
+            })?;
+        } else if flipped {
+            expanded.push(inner.opposite(None));
+        } else {
+            expanded.push(inner.clone_at_repeat(None));
+        }
+    }
+    // Moves from the sequence (including any nested invocations) record where they were invoked from.
+    for mv in &mut expanded[start..] {
+        mv.calls.push(site.clone());
     }
+    active.pop();
+    Ok(())
 }
 
 /// Expand any repeats.
 fn expand_repeats(timed_mvs: &[TimedMove]) -> Result<Vec<TimedMove>, ParseError> {
-    let mut text_pos_counts: HashMap<TextPosition, usize> = HashMap::new();
+    let mut text_pos_counts: HashMap<(Option<&str>, TextPosition), usize> = HashMap::new();
     let mut start_positions: Vec<(usize, u32)> = Vec::new();
     let mut idx = 0;
     let mut flipped = false;
@@ -517,6 +1042,8 @@ fn expand_repeats(timed_mvs: &[TimedMove]) -> Result<Vec<TimedMove>, ParseError>
                     return Err(ParseError {
                         pos: timed_mv.mv.text_pos().unwrap_or_default(),
                         msg: "found end of repeat when no repeat in progress!".to_string(),
+                        file: timed_mv.file.clone(),
+                        calls: timed_mv.calls.clone(),
                     });
                 };
                 start_pos.1 += 1;
@@ -547,9 +1074,10 @@ fn expand_repeats(timed_mvs: &[TimedMove]) -> Result<Vec<TimedMove>, ParseError>
                 let mut repeat = None;
                 if let Some(text_pos) = timed_mv.mv.text_pos() {
                     assert!(text_pos.repeat.is_none());
-                    let count = 1 + *text_pos_counts.get(&text_pos).unwrap_or(&0);
-                    debug!("  text_pos_counts[{text_pos:?}] = {count}");
-                    text_pos_counts.insert(text_pos, count);
+                    let key = (timed_mv.file.as_deref(), text_pos);
+                    let count = 1 + *text_pos_counts.get(&key).unwrap_or(&0);
+                    debug!("  text_pos_counts[{key:?}] = {count}");
+                    text_pos_counts.insert(key, count);
                     if count <= 1 {
                         repeat = None
                     } else {
@@ -578,6 +1106,8 @@ fn expand_repeats(timed_mvs: &[TimedMove]) -> Result<Vec<TimedMove>, ParseError>
         return Err(ParseError {
             pos: Default::default(),
             msg: "found end of input with repeat still pending!".to_string(),
+            file: None,
+            calls: Vec::new(),
         });
     }
     Ok(expanded)
@@ -609,7 +1139,7 @@ pub fn minimize_vert_url(input: &str) -> Result<String, ParseError> {
 
 /// Generate canonicalized input, using smallest parameter expressions but preserving whitespace and comments.
 pub fn canonicalize(input: &str) -> Result<String, ParseError> {
-    transform_input(input, |mv| mv.text())
+    transform_input(input, |mv| mv.canonical_text())
 }
 
 /// Generate fully-expanded input, preserving whitespace and comments.
@@ -617,6 +1147,16 @@ pub fn expand(input: &str) -> Result<String, ParseError> {
     transform_input(input, |mv| mv.expanded_text())
 }
 
+/// Return the position in the input and the static information for each move, in input order.  Any text that fails
+/// to parse is skipped.
+pub fn move_infos(input: &str) -> Vec<(TextPosition, &'static moves::Info)> {
+    let (moves, _errs) = partial_moves(input);
+    moves
+        .iter()
+        .filter_map(|mv| Some((mv.mv.text_pos()?, mv.mv.id().info())))
+        .collect()
+}
+
 fn transform_input<F>(input: &str, f: F) -> Result<String, ParseError>
 where
     F: Fn(&TimedMove) -> String,
@@ -661,28 +1201,174 @@ where
 
 /// Generate SVG for the given input.
 pub fn generate(input: &str) -> Result<String, ParseError> {
-    generate_with_positions(input).map(|(svg, _text_positions, _timings)| svg)
+    generate_with_resolver(input, &MemoryResolver::default())
+}
+
+/// Generate SVG for the given input, using `resolver` to find the contents of any included files.
+pub fn generate_with_resolver(input: &str, resolver: &dyn Resolver) -> Result<String, ParseError> {
+    generate_with_errors(input, resolver).map_err(first_error)
+}
+
+/// Generate SVG for the given input, reporting every error found in the input (in text order) on failure, rather
+/// than just the first.
+pub fn generate_with_errors(
+    input: &str,
+    resolver: &dyn Resolver,
+) -> Result<String, Vec<ParseError>> {
+    generate_with_warnings(input, resolver).map(|(svg, _warnings)| svg)
+}
+
+/// Generate SVG for the given input, also returning any warnings about questionable input.
+///
+/// On failure, returns every error found in the input (in text order).
+pub fn generate_with_warnings(
+    input: &str,
+    resolver: &dyn Resolver,
+) -> Result<(String, Vec<Warning>), Vec<ParseError>> {
+    generate_internal(input, resolver, None, false).map(|out| (out.svg, out.warnings))
+}
+
+/// Check the input strictly, returning warnings about questionable input, including any physically impossible
+/// changes of foot, direction or edge between moves (as for `Info[strict=true]`).
+///
+/// On failure, returns every error found in the input (in text order).
+pub fn lint(input: &str, resolver: &dyn Resolver) -> Result<Vec<Warning>, Vec<ParseError>> {
+    generate_internal(input, resolver, None, true).map(|out| out.warnings)
+}
+
+/// Calculate the base value of each element in the input, using the given scale of values.
+///
+/// On failure, returns every error found in the input (in text order).
+pub fn score(
+    input: &str,
+    resolver: &dyn Resolver,
+    sov: &ScaleOfValues,
+) -> Result<ScoreSheet, Vec<ParseError>> {
+    let moves = expanded_moves(input, resolver)?;
+    Ok(scoring::score_moves(&moves, sov))
+}
+
+/// Check the jump elements in the input against the well-balanced program rules for a competition segment, returning
+/// a warning for each rule that is broken.
+///
+/// On failure, returns every error found in the input (in text order).
+pub fn check_rules(
+    input: &str,
+    resolver: &dyn Resolver,
+    rules: RuleSet,
+) -> Result<Vec<Warning>, Vec<ParseError>> {
+    let moves = expanded_moves(input, resolver)?;
+    Ok(rules::check_moves(&moves, rules))
+}
+
+/// Analyse the turns and steps in the input as a step sequence, estimating the level that it can achieve.  Body
+/// movements cannot be seen in a diagram, so `body_movements` indicates whether the skater will include them.
+///
+/// On failure, returns every error found in the input (in text order).
+pub fn analyze_steps(
+    input: &str,
+    resolver: &dyn Resolver,
+    body_movements: bool,
+) -> Result<StepAnalysis, Vec<ParseError>> {
+    let moves = expanded_moves(input, resolver)?;
+    Ok(steps::analyze_moves(&moves, body_movements))
 }
 
 /// Generate SVG for the given input, also returning:
 /// - a list of text positions that correspond to moves
-/// - a list of timings for each move.
+/// - a list of timings for each move
+/// - a list of warnings about questionable input.
+///
+/// On failure, returns every error found in the input (in text order).
 pub fn generate_with_positions(
     input: &str,
-) -> Result<(String, Vec<String>, Vec<usize>), ParseError> {
-    let moves = moves(input)?;
+    resolver: &dyn Resolver,
+) -> Result<GeneratedPositions, Vec<ParseError>> {
+    generate_internal(input, resolver, None, false)
+        .map(|out| (out.svg, out.positions, out.timings, out.warnings))
+}
+
+/// Generate self-contained animated SVG for the given input, where a marker for each skater moves along their
+/// track, with each move taking its duration in beats at the given `tempo` (in beats per minute).
+pub fn generate_animated(
+    input: &str,
+    resolver: &dyn Resolver,
+    tempo: u32,
+) -> Result<String, ParseError> {
+    generate_animated_with_warnings(input, resolver, tempo)
+        .map(|(svg, _warnings)| svg)
+        .map_err(first_error)
+}
+
+/// Generate animated SVG for the given input (as for [`generate_animated`]), also returning any warnings about
+/// questionable input.
+///
+/// On failure, returns every error found in the input (in text order).
+pub fn generate_animated_with_warnings(
+    input: &str,
+    resolver: &dyn Resolver,
+    tempo: u32,
+) -> Result<(String, Vec<Warning>), Vec<ParseError>> {
+    generate_internal(input, resolver, Some(animate::Animation::new(tempo)), false)
+        .map(|out| (out.svg, out.warnings))
+}
+
+/// Output of [`generate_with_positions`]: SVG, move positions, move timings and warnings.
+pub type GeneratedPositions = (String, Vec<String>, Vec<usize>, Vec<Warning>);
+
+/// Output of diagram generation.
+struct Generated {
+    svg: String,
+    /// Text positions of the moves.
+    positions: Vec<String>,
+    /// Timings of the moves.
+    timings: Vec<usize>,
+    /// Diagram title.
+    #[cfg_attr(not(feature = "pdf"), allow(dead_code))]
+    title: String,
+    /// Warnings about questionable input.
+    warnings: Vec<Warning>,
+}
+
+/// Add a warning, unless it duplicates an existing warning (e.g. from a repeat of the same move).
+fn add_warning(warnings: &mut Vec<Warning>, warning: Warning) {
+    let pos = warning.pos.at_repeat(None);
+    if !warnings.iter().any(|w| {
+        w.kind == warning.kind && w.pos == pos && w.file == warning.file && w.calls == warning.calls
+    }) {
+        warn!("{warning}");
+        warnings.push(Warning { pos, ..warning });
+    }
+}
+
+/// Parse the input and expand any includes, definitions and repeats.
+fn expanded_moves(input: &str, resolver: &dyn Resolver) -> Result<Vec<TimedMove>, Vec<ParseError>> {
+    let moves = all_moves(input)?;
     debug!("input parses as:");
     for (idx, mv) in moves.iter().enumerate() {
         debug!("  [{idx}] {}", mv.text());
     }
 
-    let moves = expand_repeats(&moves)?;
+    // Later stages stop at the first error.
+    let moves = expand_includes(moves, resolver, None, &mut Vec::new())?;
+    let moves = expand_definitions(&moves).map_err(|e| vec![e])?;
+    expand_repeats(&moves).map_err(|e| vec![e])
+}
+
+fn generate_internal(
+    input: &str,
+    resolver: &dyn Resolver,
+    mut animation: Option<animate::Animation>,
+    strict: bool,
+) -> Result<Generated, Vec<ParseError>> {
+    let moves = expanded_moves(input, resolver)?;
 
     let mut doc = Document::new().set("xmlns:xlink", "http://www.w3.org/1999/xlink");
     doc = doc.add(Comment::new(replace_double_minus(input)));
 
     let mut opts = RenderOptions {
         title: "Skating Diagram".to_string(),
+        strict,
         ..Default::default()
     };
 
@@ -709,23 +1395,79 @@ pub fn generate_with_positions(
     // Second pass: figure out a bounding box, starting at (0,0) facing 0.
     info!("========= determine bounding box ===========");
     let mut bounds: Option<Bounds> = None;
-    let mut skater = Skater::at_zero(code!(BF));
-    let mut first = true;
+    let mut track = Track::default();
+    let mut tracks = Tracks::default();
+    let mut warnings = Vec::new();
     for timed_mv in &moves {
-        let mv = &timed_mv.mv;
-        if first {
+        let placed = tracks.place(timed_mv.mv.as_ref());
+        let mv = placed.as_ref().unwrap_or(&timed_mv.mv);
+        if track.first {
             // Don't apply pre-transition for first move.
             if let Some(start_code) = mv.start() {
-                skater.code = start_code;
+                track.skater.code = start_code;
             }
-            debug!("start: {skater}");
+            debug!("start: {}", track.skater);
         } else {
-            let pre_transition = mv.pre_transition(skater.code);
-            skater = skater + pre_transition;
-            debug!("pre:  add {pre_transition} ==> {skater}");
+            if let (Some(kind), Some(pos)) = (
+                mv.pre_transition_warning(track.skater.code, opts.strict),
+                mv.text_pos(),
+            ) {
+                let (file, calls) = (timed_mv.file.clone(), timed_mv.calls.clone());
+                add_warning(
+                    &mut warnings,
+                    Warning {
+                        pos,
+                        kind,
+                        file,
+                        calls,
+                    },
+                );
+            }
+            let pre_transition = mv.pre_transition(track.skater.code);
+            track.skater = track.skater + pre_transition;
+            debug!("pre:  add {pre_transition} ==> {}", track.skater);
         };
 
-        let move_bounds = mv.bounds(&skater);
+        if let (Some(rink), Some(pos)) = (for_skating(mv.as_ref(), tracks.rink), mv.text_pos()) {
+            // Check the tracks of any partner or formation skaters too.
+            let mut skaters = vec![track.skater];
+            if let Some(partner) = track.partner {
+                skaters.push(partner.skater_for(&track.skater));
+            }
+            if let Some(formation) = &track.formation {
+                skaters.extend(formation.skaters_for(&track.skater));
+            }
+            if skaters.iter().any(|s| rink.outside(mv.as_ref(), s)) {
+                let kind = WarningKind::OutsideRink;
+                let (file, calls) = (timed_mv.file.clone(), timed_mv.calls.clone());
+                add_warning(
+                    &mut warnings,
+                    Warning {
+                        pos,
+                        kind,
+                        file,
+                        calls,
+                    },
+                );
+            }
+        }
+
+        let mut move_bounds = mv.bounds(&track.skater);
+        if let (Some(partner), Some(bounds)) =
+            (for_skating(mv.as_ref(), track.partner), &mut move_bounds)
+        {
+            if let Some(partner_bounds) = mv.bounds(&partner.skater_for(&track.skater)) {
+                bounds.encompass_bounds(&partner_bounds);
+            }
+        }
+        if let (Some(formation), Some(bounds)) = (
+            for_skating(mv.as_ref(), track.formation.as_ref()),
+            &mut move_bounds,
+        ) {
+            if let Some(formation_bounds) = formation.bounds_for(mv.as_ref(), &track.skater) {
+                bounds.encompass_bounds(&formation_bounds);
+            }
+        }
 
         if let Some(move_bounds) = move_bounds {
             match &mut bounds {
@@ -750,11 +1492,13 @@ pub fn generate_with_positions(
         }
 
         let transition = mv.transition();
-        let after = skater + transition;
-        debug!("post: {skater} + {transition} ==> {after}");
+        let after = track.skater + transition;
+        debug!("post: {} + {transition} ==> {after}", track.skater);
 
-        skater = after;
-        first = false;
+        track.skater = after;
+        track.first = false;
+
+        tracks.configure(timed_mv.mv.as_ref(), &mut track);
     }
     let bounds = bounds.unwrap_or_default();
     opts.bounds = bounds;
@@ -772,30 +1516,32 @@ pub fn generate_with_positions(
     info!("========= render ===========");
     let mut text_positions = Vec::new();
     let mut timings = Vec::new();
-    let mut skater = Skater {
-        pos: Position::default(),
-        dir: Direction::new(0),
-        code: code!(BF),
-    };
-    let mut first = true;
+    let mut track = Track::default();
+    let mut tracks = Tracks::default();
     for timed_mv in &moves {
-        let mv = &timed_mv.mv;
-        if first {
+        let placed = tracks.place(timed_mv.mv.as_ref());
+        let mv = placed.as_ref().unwrap_or(&timed_mv.mv);
+        if track.first {
             // Don't apply pre-transition for first move.
             if let Some(start_code) = mv.start() {
-                skater.code = start_code;
+                track.skater.code = start_code;
             }
-            debug!("start: {skater}");
+            debug!("start: {}", track.skater);
         } else {
-            let pre_transition = mv.pre_transition(skater.code);
-            skater = skater + pre_transition;
-            debug!("pre:  add {pre_transition} ==> {skater}");
+            let pre_transition = mv.pre_transition(track.skater.code);
+            track.skater = track.skater + pre_transition;
+            debug!("pre:  add {pre_transition} ==> {}", track.skater);
         };
 
         info!("{:?} => {:?}", mv.start(), mv.end());
         debug!("perform: {}", mv.text());
+        opts.file = timed_mv.file.clone();
         if opts.markers {
-            doc = doc.add(use_at(&skater, &SvgId("start-mark".to_string()), &opts));
+            doc = doc.add(use_at(
+                &track.skater,
+                &SvgId("start-mark".to_string()),
+                &opts,
+            ));
         }
         let show_marker = opts.markers;
 
@@ -809,18 +1555,28 @@ pub fn generate_with_positions(
             (None, None) => None,
         };
 
-        doc = mv.render(doc, &skater, &mut opts, None);
+        // Parts of skating moves that go outside the rink may be drawn in a highlight colour.
+        opts.highlight_rink = for_skating(mv.as_ref(), tracks.rink)
+            .filter(|rink| rink.highlight().is_some())
+            .cloned();
+        doc = mv.render(doc, &track.skater, &mut opts, None);
+        if let Some(partner) = for_skating(mv.as_ref(), track.partner) {
+            doc = partner.render_for(doc, mv.as_ref(), &track.skater, &mut opts);
+        }
+        if let Some(formation) = for_skating(mv.as_ref(), track.formation.as_ref()) {
+            doc = formation.render_for(doc, mv.as_ref(), &track.skater, &mut opts);
+        }
 
         let transition = mv.transition();
-        let after = skater + transition;
-        debug!("post: {skater} + {transition} ==> {after}");
+        let after = track.skater + transition;
+        debug!("post: {} + {transition} ==> {after}", track.skater);
         if show_marker {
             doc = doc.add(use_at(&after, &SvgId("end-mark".to_string()), &opts));
         }
 
         // Accumulate the collection of text positions for the move specifications along the way.
         if let Some(text_pos) = mv.text_pos() {
-            text_positions.push(text_pos);
+            text_positions.push(opts.pos_id(text_pos));
             let timing = if !mv.id().info().visible {
                 // Non-visible moves take no time
                 0
@@ -832,6 +1588,15 @@ pub fn generate_with_positions(
                 1
             };
             timings.push(timing);
+            if let (Some(animation), MoveId::Skating(_)) = (&mut animation, mv.id()) {
+                animation.add(
+                    &tracks.current,
+                    &track.skater,
+                    mv.as_ref(),
+                    timing,
+                    opts.colour(),
+                );
+            }
         }
 
         if mv.id().info().visible {
@@ -846,8 +1611,13 @@ pub fn generate_with_positions(
             };
         }
 
-        skater = after;
-        first = false;
+        track.skater = after;
+        track.first = false;
+
+        // The running count and colour of the active skater are held in the render options while rendering.
+        (track.auto_count, track.colour) = (opts.auto_count, opts.colour.take());
+        tracks.configure(timed_mv.mv.as_ref(), &mut track);
+        (opts.auto_count, opts.colour) = (track.auto_count, track.colour.take());
     }
 
     if let Some(grid) = opts.grid {
@@ -908,6 +1678,10 @@ pub fn generate_with_positions(
         );
     }
 
+    if let Some(animation) = &animation {
+        doc = animation.render(doc, &opts);
+    }
+
     // Set the viewBox to the outer bounds.
     doc = doc.set(
         "viewBox",
@@ -921,16 +1695,17 @@ pub fn generate_with_positions(
     );
 
     let mut svg = Vec::new();
-    svg::write(&mut svg, &doc)?;
-    let svg = String::from_utf8(svg)?;
+    svg::write(&mut svg, &doc).map_err(|e| vec![e.into()])?;
+    let svg = String::from_utf8(svg).map_err(|e| vec![e.into()])?;
     trace!("emit SVG:\n{svg}");
 
-    let text_positions = text_positions
-        .iter()
-        .map(|pos| pos.unique_id())
-        .collect::<Vec<_>>();
-
-    Ok((svg, text_positions, timings))
+    Ok(Generated {
+        svg,
+        positions: text_positions,
+        timings,
+        title: opts.title,
+        warnings,
+    })
 }
 
 /// Replace all "--" instances in a string with the equivalent HTML entity.
@@ -954,4 +1729,874 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_all_errors() {
+        let input = "LFO;XYZ;RFI\nLFO [len=-1] # comment\nRFI ABC\nBad # comment\nLBO";
+        let errs = generate_with_errors(input, &MemoryResolver::default()).unwrap_err();
+        let got = errs
+            .iter()
+            .map(|e| (e.pos.row, e.pos.col, e.pos.count))
+            .collect::<Vec<_>>();
+        assert_eq!(got, vec![(0, 4, 3), (1, 0, 12), (2, 4, 3), (3, 0, 3)]);
+        assert_eq!(errs[0].msg, "failed to parse");
+
+        // The moves that parse are still available.
+        let infos = move_infos(input);
+        assert_eq!(infos.len(), 4);
+        assert_eq!(infos[3].0.row, 4);
+
+        // Later stages still stop at the first error.
+        let errs = generate_with_errors("LFO;:|;:|", &MemoryResolver::default()).unwrap_err();
+        assert_eq!(errs.len(), 1);
+    }
+
+    #[test]
+    fn test_warnings() {
+        let tests = [
+            ("LFO;xf-RFI;xb-LBO", vec![]),
+            (
+                "LFO;xf-LFI",
+                vec![(text_pos!(0, 4), WarningKind::CrossNoFootChange)],
+            ),
+            (
+                "BF;xf-LFO\nLFO;xb-LBI;xb-BB",
+                vec![
+                    (text_pos!(0, 3), WarningKind::CrossFromTwoFeet),
+                    (text_pos!(1, 11), WarningKind::CrossToTwoFeet),
+                ],
+            ),
+            // Repeated moves only warn once.
+            (
+                "|:;LFO;xf-LFI;:|",
+                vec![(text_pos!(0, 7), WarningKind::CrossNoFootChange)],
+            ),
+            // Moves that leave the rink, including through the rounded corners.
+            (
+                "Rink[width=1000,length=2000]\nWarp[x=300,y=300]\nLFO;RFO;LFO;RFO;LFO",
+                vec![
+                    (text_pos!(2, 12), WarningKind::OutsideRink),
+                    (text_pos!(2, 16), WarningKind::OutsideRink),
+                ],
+            ),
+            (
+                "Rink[width=1000,length=1000]\nWarp[x=20,y=20,dir=315]\nLFO",
+                vec![(text_pos!(2, 0), WarningKind::OutsideRink)],
+            ),
+            (
+                "Rink[width=1000,length=1000,rotate=90]\nWarp[x=-500,y=200]\nLFO",
+                vec![],
+            ),
+            // The tracks of partners and formations are checked too.
+            (
+                "Rink[width=1000,length=1000]\nPartner[side=600]\nWarp[x=500,y=300]\nLFO",
+                vec![(text_pos!(3, 0), WarningKind::OutsideRink)],
+            ),
+            (
+                "Rink[width=1000,length=1000]\nWarp[x=500,y=300]\nFormation[count=3,spacing=400]\nLFO",
+                vec![(text_pos!(3, 0), WarningKind::OutsideRink)],
+            ),
+        ];
+        for (input, want) in tests {
+            let (_svg, warnings) =
+                generate_with_warnings(input, &MemoryResolver::default()).unwrap();
+            let got = warnings
+                .iter()
+                .map(|w| ((w.pos.row, w.pos.col), w.kind))
+                .collect::<Vec<_>>();
+            let want = want
+                .into_iter()
+                .map(|(pos, kind)| ((pos.row, pos.col), kind))
+                .collect::<Vec<_>>();
+            assert_eq!(got, want, "for '{input}'");
+        }
+    }
+
+    #[test]
+    fn test_tracks_per_skater() {
+        // A partner track only follows the skater that it was configured for.
+        let input = "Skater[name=\"A\"]\nPartner[side=-80,colour=\"red\"]\nLFO\nSkater[name=\"B\"]\nRFO\nSkater[name=\"A\"]\nLFI";
+        let svg = generate(input).unwrap();
+        assert_eq!(svg.matches("stroke:red; stroke-width").count(), 2);
+
+        // All skaters share the one rink, whichever skater is active when it is given.
+        for input in [
+            "Rink[width=1000,length=1000]\nSkater[name=\"B\"]\nWarp[x=20,y=20,dir=315]\nLFO",
+            "Skater[name=\"B\"]\nRink[width=1000,length=1000]\nSkater[name=\"A\"]\nWarp[x=20,y=20,dir=315]\nLFO",
+        ] {
+            let (_svg, warnings) =
+                generate_with_warnings(input, &MemoryResolver::default()).unwrap();
+            let got = warnings.iter().map(|w| w.kind).collect::<Vec<_>>();
+            assert_eq!(got, vec![WarningKind::OutsideRink], "for {input}");
+        }
+    }
+
+    #[test]
+    fn test_partner_labels() {
+        // Labels for hops and for the labels inside compound moves also switch feet for an opposite partner.
+        let input = "Partner[side=-80,opposite=true]\nLFO-Tw1\nLF-Hop[label=\"LF hop\"]";
+        let svg = generate(input).unwrap();
+        for text in ["LFO-Tw1", "RFO-Tw1", "LF hop", "RF hop"] {
+            assert!(svg.contains(&format!("\n{text}\n")), "for {text}");
+        }
+    }
+
+    #[test]
+    fn test_generate_animated() {
+        let input = "LFO;/2RFI;LFO\nSkater[name=\"B\",colour=\"red\"]\nRFO";
+        let resolver = MemoryResolver::default();
+        let motion_re = regex::Regex::new(
+            r#"<animateMotion begin="([0-9.]+)s" dur="([0-9.]+)s" fill="([a-z]+)""#,
+        )
+        .unwrap();
+        let tests = [
+            (
+                60,
+                vec![
+                    ("0.000", "1.000"),
+                    ("1.000", "2.000"),
+                    ("3.000", "1.000"),
+                    ("0.000", "1.000"),
+                ],
+            ),
+            (
+                120,
+                vec![
+                    ("0.000", "0.500"),
+                    ("0.500", "1.000"),
+                    ("1.500", "0.500"),
+                    ("0.000", "0.500"),
+                ],
+            ),
+        ];
+        for (tempo, want) in tests {
+            let svg = generate_animated(input, &resolver, tempo).unwrap();
+            let got = motion_re
+                .captures_iter(&svg)
+                .map(|caps| {
+                    assert_eq!(&caps[3], "freeze");
+                    (caps[1].to_string(), caps[2].to_string())
+                })
+                .collect::<Vec<_>>();
+            let want = want
+                .into_iter()
+                .map(|(begin, dur)| (begin.to_string(), dur.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(got, want, "at tempo {tempo}");
+
+            // One marker per skater, in the skater's colour.
+            for colour in ["black", "red"] {
+                let marker = format!("<g style=\"stroke:{colour}; fill:{colour};");
+                assert_eq!(svg.matches(&marker).count(), 1, "for {colour}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_lint() {
+        let tests = [
+            ("LFO;RFI;LFO3;LBI;RBO", vec![]),
+            ("LFO;LF;LFI-CoE;LFO", vec![]),
+            ("BF;LBO;BB;LFI", vec![]),
+            (
+                "LFO;LFI",
+                vec![WarningKind::SameFootEdgeChange {
+                    from: code!(LFO),
+                    to: code!(LFI),
+                }],
+            ),
+            (
+                "RFO;RBO",
+                vec![WarningKind::SameFootDirectionChange {
+                    from: code!(RFO),
+                    to: code!(RBO),
+                }],
+            ),
+            (
+                "LFO3;LFI",
+                vec![WarningKind::SameFootDirectionChange {
+                    from: code!(LBI),
+                    to: code!(LFI),
+                }],
+            ),
+            ("LFO;xf-LFI", vec![WarningKind::CrossNoFootChange]),
+        ];
+        for (input, want) in tests {
+            let got = lint(input, &MemoryResolver::default()).unwrap();
+            let got = got.into_iter().map(|w| w.kind).collect::<Vec<_>>();
+            assert_eq!(got, want, "for '{input}'");
+
+            // Only checked if enabled.
+            let (_svg, warnings) =
+                generate_with_warnings(input, &MemoryResolver::default()).unwrap();
+            assert!(warnings.len() <= want.len(), "for '{input}'");
+            let strict = format!("Info[strict=true];{input}");
+            let (_svg, warnings) =
+                generate_with_warnings(&strict, &MemoryResolver::default()).unwrap();
+            assert_eq!(warnings.len(), want.len(), "for '{strict}'");
+        }
+        let warnings = lint("LFO;\n  RFI;RBO", &MemoryResolver::default()).unwrap();
+        assert_eq!((warnings[0].pos.row, warnings[0].pos.col), (1, 6));
+        let warnings = lint("LFO;LFI", &MemoryResolver::default()).unwrap();
+        assert_eq!(
+            warnings[0].to_string(),
+            "1:5: warning: edge changes from LFO to LFI on the same foot without a change-of-edge move"
+        );
+    }
+
+    #[test]
+    fn test_jump_combos() {
+        let tests = [
+            ("LBO-3Lz+3T", code!(LBO), code!(RBO)),
+            ("RBO-2Lo+2Lo+2T", code!(RBO), code!(RBO)),
+            ("LFO-3A+2A+SEQ", code!(LFO), code!(RBO)),
+            ("RFO-2A+1A+SEQ", code!(RFO), code!(LBO)),
+        ];
+        for (input, start, end) in tests {
+            let moves = moves(input).unwrap();
+            assert_eq!(moves.len(), 1, "for '{input}'");
+            let mv = &moves[0].mv;
+            assert_eq!(mv.text(), input);
+            assert_eq!(
+                (mv.start(), mv.end()),
+                (Some(start), Some(end)),
+                "for '{input}'"
+            );
+            let opposite = mv.opposite(None);
+            assert_eq!(opposite.text(), format!("opposite({input})"));
+            assert_eq!(opposite.start(), Some(start.opposite()), "for '{input}'");
+        }
+        let err = moves("LBO-2Lz+2Lz").unwrap_err();
+        assert_eq!(
+            err.msg,
+            "2Lz takes off from LBO so cannot follow a RBO landing in a combination"
+        );
+        assert!(moves("LBI-1Lz+1T").is_err());
+    }
+
+    #[test]
+    fn test_minor_jumps() {
+        let tests = [
+            ("LFO-Wz", code!(RBO)),
+            ("RBO-1Eu", code!(LBI)),
+            ("LBI-HalfF", code!(LFO)),
+            ("LBO-HalfLz", code!(LFO)),
+            ("LBI-Split", code!(LFO)),
+            ("LBI-Stag", code!(LFO)),
+            ("RBI-Walley", code!(RBO)),
+            ("RBI-TWalley", code!(RBO)),
+            ("LFO-Bunny", code!(LFO)),
+        ];
+        for (input, end) in tests {
+            let moves = moves(input).unwrap();
+            let mv = &moves[0].mv;
+            assert_eq!(mv.text(), input);
+            assert_eq!(mv.end(), Some(end), "for '{input}'");
+            assert_eq!(
+                mv.opposite(None).end(),
+                Some(end.opposite()),
+                "for '{input}'"
+            );
+        }
+        let err = moves("LFO-Walley").unwrap_err();
+        assert_eq!(err.msg, "Unsupported entry edge LFO for Walley");
+        let err = moves("RBO-2Eu").unwrap_err();
+        assert_eq!(err.msg, "Euler only has a single rotation");
+        assert!(moves("LBO-3Lz+1Eu+3S").is_ok());
+    }
+
+    #[test]
+    fn test_score() {
+        let sov = ScaleOfValues::default();
+        let input = "LBO-3Lz+3T\nRBO-1Eu\n|: ; LFO-2A ; :|\nLFO-FCCoSp\nLFO-Wz\nRFO3\nLFO3\nRFO-Tw1\nLFI-Ctr\nRFI-Br\nLFO-FCUSp";
+        let sheet = score(input, &MemoryResolver::default(), &sov).unwrap();
+        let got = sheet
+            .elements
+            .iter()
+            .map(|e| (e.pos.row, e.code.as_str(), e.base_value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            got,
+            vec![
+                (0, "3Lz+3T", Some(Points(1010))),
+                (1, "1Eu", Some(Points(50))),
+                (2, "2A", Some(Points(330))),
+                (2, "2A", Some(Points(330))),
+                (3, "FCCoSpB", Some(Points(200))),
+                (5, "StSq1", Some(Points(180))),
+                (10, "FCUSpB", None),
+            ]
+        );
+        assert_eq!(sheet.total, Points(2100));
+        assert_eq!(sheet.total.to_string(), "21.00");
+        assert!(sheet.to_string().contains("FCUSpB                -"));
+
+        let sov = ScaleOfValues::parse("# comment\n3Lz 6\n3T 4.2 # trailing\n").unwrap();
+        assert_eq!(sov.base_value("3Lz"), Some(Points(600)));
+        let sheet = score("LBO-3Lz+3T", &MemoryResolver::default(), &sov).unwrap();
+        assert_eq!(sheet.total, Points(1020));
+        let sheet = score("LFO-2A\nLBO-3Lz", &MemoryResolver::default(), &sov).unwrap();
+        assert_eq!(sheet.elements[0].base_value, None);
+        assert_eq!(sheet.total, Points(600));
+
+        let err = ScaleOfValues::parse("3Lz 5.90\n3T four").unwrap_err();
+        assert_eq!(err.pos.row, 1);
+        assert!(ScaleOfValues::parse("3Lz 5.901").is_err());
+    }
+
+    #[test]
+    fn test_check_rules() {
+        use JumpCount::*;
+        use JumpKind::*;
+        let free = "LFO-3A\nLBO-3Lz+3T\nLBI-3F\nLBO-3Lz\nRBO-3Lo+1Eu+3S\nLBI-2S\nLFO-2A+2A+SEQ";
+        let tests = [
+            (free, RuleSet::SeniorFree, vec![]),
+            ("LFO-3A\nLBO-3Lz+3T\nLBI-3F", RuleSet::SeniorShort, vec![]),
+            (
+                "LFO-2A\nLBO-3Lz+3T\nLBO-3Lz",
+                RuleSet::JuniorShort,
+                vec![(
+                    2,
+                    WarningKind::JumpRepeated {
+                        kind: Lutz,
+                        count: Triple,
+                    },
+                )],
+            ),
+            (
+                "LFO-2A\nLBO-4Lz+3T\nLFO-2A+2A+SEQ",
+                RuleSet::JuniorShort,
+                vec![
+                    (1, WarningKind::TooManyRotations { max: Triple }),
+                    (2, WarningKind::TooManyCombinations { max: 1 }),
+                    (2, WarningKind::SequenceNotAllowed),
+                ],
+            ),
+            (
+                "LBO-3Lz\nLBO-3Lz\nLBO-3Lz+3T",
+                RuleSet::SeniorFree,
+                vec![
+                    (
+                        1,
+                        WarningKind::RepeatNotInCombination {
+                            kind: Lutz,
+                            count: Triple,
+                        },
+                    ),
+                    (
+                        2,
+                        WarningKind::JumpRepeated {
+                            kind: Lutz,
+                            count: Triple,
+                        },
+                    ),
+                    (0, WarningKind::MissingAxel),
+                ],
+            ),
+            (
+                "LFO-1A\nRBO-3T+3T\nRBO-3Lo+3Lo\nLBO-3Lz+2T\nLBO-3Lz",
+                RuleSet::SeniorFree,
+                vec![(4, WarningKind::TooManyRepeatedJumps { max: 2 })],
+            ),
+            (
+                "LFO-1A\nRBO-4T+4T\nRBO-4Lo+4Lo+2T\nLBO-2Lz+2T+2T",
+                RuleSet::SeniorFree,
+                vec![
+                    (2, WarningKind::TooManyRepeatedQuads { max: 1 }),
+                    (3, WarningKind::TooManyThreeJumpCombinations),
+                ],
+            ),
+            (
+                &format!("{free}\nLBI-1F"),
+                RuleSet::SeniorFree,
+                vec![(7, WarningKind::TooManyJumpElements { max: 7 })],
+            ),
+        ];
+        for (input, rules, want) in tests {
+            let got = check_rules(input, &MemoryResolver::default(), rules).unwrap();
+            let got = got
+                .into_iter()
+                .map(|w| (w.pos.row, w.kind))
+                .collect::<Vec<_>>();
+            assert_eq!(got, want, "for '{input}' with {rules:?}");
+        }
+    }
+
+    #[test]
+    fn test_analyze_steps() {
+        let resolver = MemoryResolver::default();
+        let got = analyze_steps(
+            "RFO3\nLFO3\nLFO\nLFI-OpMo\nRFO-Br\nLBI-Br\nLFO-Ctr\nRBO-Ctr\nRFO-Rk",
+            &resolver,
+            false,
+        )
+        .unwrap();
+        let turns = got
+            .turns
+            .iter()
+            .map(|t| (t.pos.row, t.turn, t.direction))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            turns,
+            vec![
+                (0, TurnType::ThreeTurn, RotationDirection::Clockwise),
+                (1, TurnType::ThreeTurn, RotationDirection::AntiClockwise),
+                (3, TurnType::Mohawk, RotationDirection::Clockwise),
+                (4, TurnType::Bracket, RotationDirection::AntiClockwise),
+                (5, TurnType::Bracket, RotationDirection::Clockwise),
+                (6, TurnType::Counter, RotationDirection::Clockwise),
+                (7, TurnType::Counter, RotationDirection::Clockwise),
+                (8, TurnType::Rocker, RotationDirection::Clockwise),
+            ]
+        );
+        assert_eq!(got.counts[0], (TurnType::ThreeTurn, 2));
+        assert_eq!(got.variety, Variety::Variety);
+
+        let got = analyze_steps("RFO3\nLFO3\nLFO\nLFI-OpMo", &resolver, false).unwrap();
+        assert_eq!(got.variety, Variety::None);
+        assert_eq!(got.element_code(), "StSqB");
+
+        // Combinations need three different turns in a row on the same foot.
+        let tests = [
+            ("RFO3\nRBI-Br\nRFO-Rk\nLFO3\nLBI-Br\nLFO-Rk", true),
+            ("RFO3\nRBI3\nRFO3\nLFO3\nLBI3\nLFO3", false),
+            ("RFO3\nRBI3\nRFO-Br\nRBI-Rk\nLFO3\nLBI-Br\nLFO-Rk", true),
+            ("RFO3\nRBI-Br\nRFO3\nLFO3\nLBI-Br\nLFO-Rk", false),
+        ];
+        for (input, combinations) in tests {
+            let got = analyze_steps(input, &resolver, false).unwrap();
+            assert_eq!(got.combinations, combinations, "for input: {input}");
+        }
+
+        let input = "RFO3\nRBI-Br\nRFO-Rk\nLFO3\nLBI-Br\nLFO-Rk\nRFO-Ctr\nLFO-Ctr\nRFO-Tw1\nLFO-Tw1\nRFO-Loop\nLFO-Loop\nRFI-OpCho";
+        let tests = [
+            (input, true, Variety::Complexity, 4),
+            (input, false, Variety::Complexity, 2),
+            (
+                "RFO3\nRBI-Br\nRFO-Rk\nLFO-Ctr\nLFO-Tw1\nRFO-Loop\nRFI-OpCho",
+                true,
+                Variety::Variety,
+                2,
+            ),
+            (
+                "RFO3\nLFO3\nRFO-Tw1\nLFI-Ctr\nRFI-Br",
+                false,
+                Variety::Simple,
+                1,
+            ),
+        ];
+        for (input, body_movements, variety, level) in tests {
+            let got = analyze_steps(input, &resolver, body_movements).unwrap();
+            assert_eq!(got.variety, variety, "for input: {input}");
+            assert_eq!(got.level, level, "for input: {input}");
+        }
+    }
+
+    #[test]
+    fn test_expressions() {
+        let input = "Let[name=\"L\",value=600]\nLFO[len = $L*3/2]  # comment\nRFI3[len=$L]";
+        assert_eq!(
+            canonicalize(input).unwrap(),
+            "Let[name=\"L\",value=600]\nLFO[len=$L*3/2]  # comment\nRFI3[len=$L]"
+        );
+        assert!(expand(input)
+            .unwrap()
+            .contains("\nLFO[angle=20,len=$L*3/2,label=\"\""));
+        assert_eq!(
+            minimize(input).unwrap(),
+            "Let[name=\"L\",value=600];LFO[len=900];RFI3+"
+        );
+    }
+
+    #[test]
+    fn test_rink_clip_ids() {
+        let svg = generate("Rink\nRink[width=6100,length=3000]").unwrap();
+        for id in ["clip-Rink", "clip-Rink-width-6100-length-3000"] {
+            assert!(svg.contains(&format!("<clipPath id=\"{id}\">")), "for {id}");
+            assert!(
+                svg.contains(&format!("clip-path=\"url(#{id})\"")),
+                "for {id}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rink_highlight() {
+        // Only the parts of the twizzle that leave the rink are highlighted.
+        let input = "Rink[width=1000,length=1000,highlight=\"orange\"]\nWarp[x=500,y=800]\nLFO-Tw1";
+        let svg = generate(input).unwrap();
+        assert!(svg.contains("stroke:orange; stroke-width"));
+        assert!(svg.contains("stroke:black; stroke-width"));
+        assert!(!svg.contains("stroke:orange; fill:orange"));
+
+        let svg =
+            generate("Rink[width=1000,length=1000,highlight=\"orange\"]\nWarp[x=500,y=300]\nLFO")
+                .unwrap();
+        assert!(!svg.contains("orange;"));
+    }
+
+    #[test]
+    fn test_rink_markings() {
+        let tests = [
+            (
+                "Rink[width=3000,length=6100,goals=true,faceoffs=true]",
+                vec![
+                    // Creases bulge towards the centre: downwards (sweep 0) at the top, upwards (sweep 1) at the
+                    // bottom.
+                    r#"<path d="M 1320,400 A 180,180 0 0 0 1680,400" style="stroke:red;"/>"#,
+                    r#"<path d="M 1320,5700 A 180,180 0 0 1 1680,5700" style="stroke:red;"/>"#,
+                    r#"<path d="M 1409,400 L 1409,288 L 1592,288 L 1592,400" style="stroke:red;"/>"#,
+                    r#"<path d="M 1409,5700 L 1409,5812 L 1592,5812 L 1592,5700" style="stroke:red;"/>"#,
+                    r#"<circle cx="800" cy="1000" r="450" style="stroke:red;"/>"#,
+                    r#"<circle cx="2200" cy="5100" r="450" style="stroke:red;"/>"#,
+                    r#"<circle cx="800" cy="1000" r="30" style="stroke:red; fill:red;"/>"#,
+                    // Hash marks run outwards from the sides of the circle, parallel to the goal line.
+                    r#"<path d="M 358,915 L 298,915" style="stroke:red;"/>"#,
+                    r#"<path d="M 2642,1085 L 2702,1085" style="stroke:red;"/>"#,
+                    r#"<circle cx="800" cy="2400" r="30" style="stroke:red; fill:red;"/>"#,
+                    r#"<circle cx="2200" cy="3700" r="30" style="stroke:red; fill:red;"/>"#,
+                ],
+            ),
+            (
+                "Rink[width=6100,length=3000,goals=true,faceoffs=true]",
+                vec![
+                    // Creases bulge towards the centre: rightwards (sweep 1) at the left, leftwards (sweep 0) at the
+                    // right.
+                    r#"<path d="M 400,1320 A 180,180 0 0 1 400,1680" style="stroke:red;"/>"#,
+                    r#"<path d="M 5700,1320 A 180,180 0 0 0 5700,1680" style="stroke:red;"/>"#,
+                    r#"<path d="M 400,1409 L 288,1409 L 288,1592 L 400,1592" style="stroke:red;"/>"#,
+                    r#"<circle cx="1000" cy="800" r="450" style="stroke:red;"/>"#,
+                    r#"<circle cx="5100" cy="2200" r="450" style="stroke:red;"/>"#,
+                    r#"<path d="M 915,358 L 915,298" style="stroke:red;"/>"#,
+                    r#"<path d="M 1085,2642 L 1085,2702" style="stroke:red;"/>"#,
+                    r#"<circle cx="2400" cy="800" r="30" style="stroke:red; fill:red;"/>"#,
+                    r#"<circle cx="3700" cy="2200" r="30" style="stroke:red; fill:red;"/>"#,
+                ],
+            ),
+            (
+                // Half size: positions and markings all scale down.
+                "Rink[width=1500,length=3050,goals=true,faceoffs=true]",
+                vec![
+                    r#"<path d="M 660,200 A 90,90 0 0 0 840,200" style="stroke:red;"/>"#,
+                    r#"<path d="M 704,200 L 704,144 L 796,144 L 796,200" style="stroke:red;"/>"#,
+                    r#"<circle cx="400" cy="500" r="225" style="stroke:red;"/>"#,
+                    r#"<circle cx="400" cy="500" r="15" style="stroke:red; fill:red;"/>"#,
+                    r#"<path d="M 179,458 L 149,458" style="stroke:red;"/>"#,
+                    r#"<circle cx="1100" cy="800" r="15" style="stroke:red; fill:red;"/>"#,
+                ],
+            ),
+        ];
+        for (input, want) in tests {
+            let svg = generate(input).unwrap();
+            for elt in want {
+                assert!(svg.contains(elt), "for '{input}': missing {elt}");
+            }
+            // One crease at each end, and eight face-off spots: two in each end zone and four in the neutral zone.
+            assert_eq!(svg.matches(" A ").count(), 2, "for '{input}'");
+            assert_eq!(svg.matches(r#"style="stroke:red; fill:red;""#).count(), 8);
+        }
+    }
+
+    #[test]
+    fn test_rink_presets() {
+        let tests = [
+            ("Rink[preset=\"nhl\"]", "Rink[preset=\"nhl\"]"),
+            ("Rink[preset=\"nhl\",width=2591]", "Rink[preset=\"nhl\"]"),
+            (
+                "Rink[preset=\"isu\",mid-lines=900]",
+                "Rink[mid-lines=900,preset=\"isu\"]",
+            ),
+            ("Rink[x=-100,rotate=90]", "Rink[x=-100,rotate=90]"),
+        ];
+        for (input, want) in tests {
+            assert_eq!(minimize(input).unwrap(), want, "for input: {input}");
+        }
+        assert!(expand("Rink[preset=\"community\"]")
+            .unwrap()
+            .contains("Rink[width=2000,length=4000,"));
+    }
+
+    #[test]
+    fn test_expand_includes() {
+        let mut resolver = MemoryResolver::default();
+        resolver.insert("a", "LFO\nInclude[file=\"b\"]");
+        resolver.insert("b", "RFI");
+        let moves = expand_includes(
+            moves("Include[file=\"a\"];LBO").unwrap(),
+            &resolver,
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let got = moves.iter().map(|m| m.text()).collect::<Vec<_>>();
+        assert_eq!(got, vec!["LFO", "RFI", "LBO"]);
+        let got = moves.iter().map(|m| m.file.as_deref()).collect::<Vec<_>>();
+        assert_eq!(got, vec![Some("a"), Some("b"), None]);
+
+        // Nested includes are relative to the including file.
+        let mut resolver = MemoryResolver::default();
+        resolver.insert("dir/a", "LFO\nInclude[file=\"b\"]");
+        resolver.insert("dir/b", "RFI");
+        let nested = expand_includes(
+            crate::moves("Include[file=\"dir/a\"];LBO").unwrap(),
+            &resolver,
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let got = nested.iter().map(|m| m.file.as_deref()).collect::<Vec<_>>();
+        assert_eq!(got, vec![Some("dir/a"), Some("dir/b"), None]);
+    }
+
+    #[test]
+    fn test_include_axis() {
+        // A line in an included file is placed on the rink given in the main input.
+        let mut resolver = MemoryResolver::default();
+        resolver.insert("axis", "Axis[barrier=300,start=true]");
+        let line = |svg: &str| {
+            let end = svg.find(" stroke-dasharray=\"40,20\"").unwrap();
+            let start = svg[..end].rfind("<path d=").unwrap();
+            svg[start..end].split(" id=").next().unwrap().to_string()
+        };
+        let rink = "Rink[width=1500,length=3000,rotate=90]\n";
+        let direct = generate_with_resolver(
+            &format!("{rink}Axis[barrier=300,start=true]\nLFO"),
+            &resolver,
+        )
+        .unwrap();
+        let included =
+            generate_with_resolver(&format!("{rink}Include[file=\"axis\"]\nLFO"), &resolver)
+                .unwrap();
+        assert_eq!(line(&direct), "<path d=\"M 0,300 L -3000,300\"");
+        assert_eq!(line(&included), line(&direct));
+    }
+
+    #[test]
+    fn test_include_positions() {
+        let mut resolver = MemoryResolver::default();
+        resolver.insert("a", "LFO\n:|");
+        resolver.insert("b", "LFO\nLFI");
+        resolver.insert("c.skate", "RFO");
+
+        // Errors found after the included moves have been expanded still refer to the included file.
+        let errs = generate_with_errors("RFO\nInclude[file=\"a\"]", &resolver).unwrap_err();
+        assert_eq!(errs[0].file.as_deref(), Some("a"));
+        assert_eq!((errs[0].pos.row, errs[0].pos.col), (1, 0));
+        assert!(errs[0].to_string().starts_with("a:2:1: "));
+
+        // As do warnings.
+        let warnings = lint("LFO\nInclude[file=\"b\"]", &resolver).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].file.as_deref(), Some("b"));
+        assert_eq!((warnings[0].pos.row, warnings[0].pos.col), (1, 0));
+        assert!(warnings[0].to_string().starts_with("b:2:1: warning: "));
+
+        // Included moves are identified separately from moves in the main input.
+        let (svg, positions, _timings, _warnings) =
+            generate_with_positions("LFO\nInclude[file=\"c.skate\"]", &resolver).unwrap();
+        assert_eq!(positions, vec!["r_0_c_0_3", "f_c-skate_r_0_c_0_3"]);
+        assert!(svg.contains("id=\"f_c-skate_r_0_c_0_3\""));
+    }
+
+    #[test]
+    fn test_expand_includes_failures() {
+        let mut resolver = MemoryResolver::default();
+        resolver.insert("bad", "LFO\n  XYZ");
+        resolver.insert("nested", "LFO\nInclude[file=\"bad\"]");
+        resolver.insert("missing", "LFO\nInclude[file=\"none\"]");
+        resolver.insert("cycle", "LFO\nInclude[file=\"cycle2\"]");
+        resolver.insert("cycle2", "Include[file=\"cycle\"]");
+        let tests = [
+            (
+                "Include[file=\"none\"]",
+                None,
+                text_pos!(0, 0),
+                "failed to include",
+            ),
+            (
+                "LFO;Include[file=\"bad\"]",
+                Some("bad"),
+                text_pos!(1, 2),
+                "failed to parse",
+            ),
+            (
+                "Include[file=\"nested\"]",
+                Some("bad"),
+                text_pos!(1, 2),
+                "failed to parse",
+            ),
+            (
+                "Include[file=\"missing\"]",
+                Some("missing"),
+                text_pos!(1, 0),
+                "failed to include",
+            ),
+            (
+                "Include[file=\"cycle\"]",
+                Some("cycle2"),
+                text_pos!(0, 0),
+                "already being included",
+            ),
+        ];
+        for (input, want_file, want_pos, want_err) in tests {
+            let errs = expand_includes(moves(input).unwrap(), &resolver, None, &mut Vec::new())
+                .unwrap_err();
+            assert_eq!(errs.len(), 1, "for '{input}'");
+            let err = &errs[0];
+            assert_eq!(err.file.as_deref(), want_file, "for '{input}'");
+            assert_eq!(
+                (err.pos.row, err.pos.col),
+                (want_pos.row, want_pos.col),
+                "for '{input}'"
+            );
+            assert!(
+                err.msg.contains(want_err),
+                "for '{input}': got '{}'",
+                err.msg
+            );
+        }
+    }
+
+    #[test]
+    fn test_expand_includes_all_errors() {
+        // Every error in every included file is reported, each with the file that it came from.
+        let mut resolver = MemoryResolver::default();
+        resolver.insert("a", "XYZ\nLFO\nABC");
+        resolver.insert("b", "LFO[len=$L]");
+        let errs = generate_with_errors(
+            "Include[file=\"a\"]\nInclude[file=\"b\"]\nInclude[file=\"c\"]",
+            &resolver,
+        )
+        .unwrap_err();
+        let got = errs
+            .iter()
+            .map(|e| (e.file.as_deref(), e.pos.row, e.pos.col))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            got,
+            vec![
+                (Some("a"), 0, 0),
+                (Some("a"), 2, 0),
+                (Some("b"), 0, 0),
+                (None, 2, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_include_variables() {
+        // An included file can use the variables defined before the include, but not those defined after it.
+        let mut resolver = MemoryResolver::default();
+        resolver.insert("a", "LFO[len=$L*2]");
+        let included = expand_includes(
+            moves("Let[name=\"L\",value=300]\nInclude[file=\"a\"]").unwrap(),
+            &resolver,
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(included[1].text(), moves("LFO[len=600]").unwrap()[0].text());
+
+        let errs = expand_includes(
+            moves("Include[file=\"a\"]\nLet[name=\"L\",value=300]").unwrap(),
+            &resolver,
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+        assert_eq!(errs[0].file.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_expand_definitions() {
+        let input =
+            "Define[name=\"a\"]\nLFO;RFI\nEnd\nCall[name=\"a\"]\nCall[name=\"a\",opposite=true]";
+        let moves = expand_definitions(&moves(input).unwrap()).unwrap();
+        let got = moves.iter().map(|m| m.text()).collect::<Vec<_>>();
+        assert_eq!(got, vec!["LFO", "RFI", "RFO", "LFI"]);
+    }
+
+    #[test]
+    fn test_expand_definitions_failures() {
+        let tests = [
+            ("End", text_pos!(0, 0), "no definition in progress"),
+            ("Define[name=\"a\"]\nLFO", text_pos!(0, 0), "still pending"),
+            ("Call[name=\"a\"]", text_pos!(0, 0), "no definition found"),
+            (
+                "Define[name=\"a\"]\nEnd\nDefine[name=\"a\"]\nEnd",
+                text_pos!(2, 0),
+                "already defined",
+            ),
+            (
+                "Define[name=\"a\"]\nDefine[name=\"b\"]\nEnd\nEnd",
+                text_pos!(1, 0),
+                "inside definition",
+            ),
+            (
+                "Define[name=\"a\"]\n:|\nEnd",
+                text_pos!(1, 0),
+                "no repeat in progress",
+            ),
+            (
+                "Define[name=\"a\"]\nLFO\n  Call[name=\"b\"]\nEnd\nCall[name=\"a\"]",
+                text_pos!(2, 2),
+                "(in \"a\" invoked at 5:1)",
+            ),
+            (
+                "Define[name=\"a\"]\nCall[name=\"a\"]\nEnd\nLFO;Call[name=\"a\"]",
+                text_pos!(1, 0),
+                "invokes itself! (in \"a\" invoked at 4:5)",
+            ),
+        ];
+        for (input, want_pos, want_err) in tests {
+            let err = expand_definitions(&moves(input).unwrap()).unwrap_err();
+            assert_eq!(
+                (err.pos.row, err.pos.col),
+                (want_pos.row, want_pos.col),
+                "for '{input}'"
+            );
+            assert!(
+                err.to_string().contains(want_err),
+                "for '{input}': got '{err}'"
+            );
+        }
+
+        // Errors in nested invocations also identify every invocation that led to them.
+        let input = "Define[name=\"b\"]\nCall[name=\"zz\"]\nEnd\nDefine[name=\"a\"]\nCall[name=\"b\"]\nEnd\nCall[name=\"a\"]";
+        let err = expand_definitions(&moves(input).unwrap()).unwrap_err();
+        assert_eq!((err.pos.row, err.pos.col), (1, 0));
+        let got = err
+            .calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.pos.row, c.pos.col))
+            .collect::<Vec<_>>();
+        assert_eq!(got, vec![("b", 4, 0), ("a", 6, 0)]);
+    }
+
+    #[test]
+    fn test_call_site_warnings() {
+        // Warnings for moves in a definition refer to both the definition and the invocation.
+        let input = "Define[name=\"a\"]\nxf-LFI\nEnd\nLFO;Call[name=\"a\"]";
+        let (_svg, warnings) = generate_with_warnings(input, &MemoryResolver::default()).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!((warnings[0].pos.row, warnings[0].pos.col), (1, 0));
+        let got = warnings[0]
+            .calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.pos.row, c.pos.col, c.file.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(got, vec![("a", 3, 4, None)]);
+        assert!(warnings[0]
+            .to_string()
+            .ends_with(" (in \"a\" invoked at 4:5)"));
+
+        // Each invocation gets its own warning.
+        let input = "Define[name=\"a\"]\nxf-LFI\nEnd\nLFO;Call[name=\"a\"]\nLFO;Call[name=\"a\"]";
+        let (_svg, warnings) = generate_with_warnings(input, &MemoryResolver::default()).unwrap();
+        let got = warnings
+            .iter()
+            .map(|w| w.calls[0].pos.row)
+            .collect::<Vec<_>>();
+        assert_eq!(got, vec![3, 4]);
+    }
 }
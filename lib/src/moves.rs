@@ -3,34 +3,45 @@
 //! Skating move definitions.
 
 use crate::{
-    cm, Centimetres, Code, Foot, JumpCount, Move, MoveParam, ParseError, Position, PreTransition,
-    Rotation, SkatingDirection::*, SpatialTransition, TextPosition, Transition,
+    cm, Centimetres, Code, Edge, Foot, JumpCombo, JumpCount, JumpKind, Move, MoveParam, ParseError,
+    Position, PreTransition, Rotation, SkatingDirection::*, SpatialTransition, SpinKind,
+    TextPosition, Transition, WarningKind,
 };
 use log::warn;
 use serde::Serialize;
+use std::collections::HashMap;
 
+pub(crate) mod axis;
 pub(crate) mod both;
 pub(crate) mod bracket;
 pub(crate) mod choctaw;
 pub(crate) mod coe;
 pub(crate) mod compound;
 pub(crate) mod counter;
+pub(crate) mod define;
 pub(crate) mod edge;
+pub(crate) mod figure;
+pub(crate) mod formation;
 pub(crate) mod hop;
+pub(crate) mod include;
 pub(crate) mod info;
 pub(crate) mod jump;
 pub(crate) mod label;
 pub(crate) mod loopfig; // Name avoids clash with keyword `loop`
 pub(crate) mod mohawk;
+pub(crate) mod partner;
 pub(crate) mod repeat;
 pub(crate) mod rink;
 pub(crate) mod rocker;
 pub(crate) mod shift;
+pub(crate) mod skater;
+pub(crate) mod spin;
 pub(crate) mod straight;
 pub(crate) mod text;
 pub(crate) mod three;
 pub(crate) mod title;
 pub(crate) mod twizzle;
+pub(crate) mod variable;
 pub(crate) mod warp;
 
 #[cfg(test)]
@@ -41,6 +52,8 @@ pub fn parse_code(input: &str, pos: TextPosition) -> Result<(&str, Code), ParseE
     crate::parser::types::parse_code(input).map_err(|_e| ParseError {
         pos,
         msg: "Unrecognized edge code".to_string(),
+        file: None,
+        calls: Vec::new(),
     })
 }
 
@@ -52,6 +65,8 @@ pub fn parse_pre_transition(
     crate::parser::types::parse_pre_transition(input).map_err(|_e| ParseError {
         pos,
         msg: "Unrecognized prefix for move".to_string(),
+        file: None,
+        calls: Vec::new(),
     })
 }
 
@@ -60,6 +75,8 @@ pub fn edge_err(pos: TextPosition, code: Code, info: Info) -> ParseError {
     ParseError {
         pos,
         msg: format!("Unsupported entry edge {code} for {}", info.name),
+        file: None,
+        calls: Vec::new(),
     }
 }
 
@@ -124,14 +141,30 @@ pub static INFO: &[Info] = &[
     jump::Flip::INFO,
     jump::Lutz::INFO,
     jump::Axel::INFO,
+    jump::Euler::INFO,
+    jump::Combo::INFO,
+    jump::WaltzJump::INFO,
+    jump::HalfFlip::INFO,
+    jump::HalfLutz::INFO,
+    jump::SplitJump::INFO,
+    jump::StagJump::INFO,
+    jump::Walley::INFO,
+    jump::ToeWalley::INFO,
+    jump::BunnyHop::INFO,
+    spin::Spin::INFO,
+    figure::Figure::INFO,
     // Then pseudo-moves.
     warp::Warp::INFO,
     shift::Shift::INFO,
     rink::Rink::INFO,
+    axis::Axis::INFO,
     info::Info::INFO,
     title::Title::INFO,
     text::Text::INFO,
     label::Label::INFO,
+    skater::Skater::INFO,
+    partner::Partner::INFO,
+    formation::Formation::INFO,
 ];
 
 /// Identifier for skating moves.
@@ -162,6 +195,22 @@ pub enum SkatingMoveId {
     Flip(JumpCount),
     Lutz(JumpCount),
     Axel(JumpCount),
+    /// Euler (half loop), which only has a single rotation.
+    Euler(JumpCount),
+    /// Combination or sequence of jumps.
+    JumpCombo(JumpCombo),
+    WaltzJump,
+    HalfFlip,
+    HalfLutz,
+    SplitJump,
+    StagJump,
+    Walley,
+    ToeWalley,
+    BunnyHop,
+    /// Spin of the given kind.
+    Spin(SpinKind),
+    /// Compulsory figure.
+    Figure,
 }
 
 impl SkatingMoveId {
@@ -189,6 +238,43 @@ impl SkatingMoveId {
             Self::Flip(_count) => &jump::Flip::INFO,
             Self::Lutz(_count) => &jump::Lutz::INFO,
             Self::Axel(_count) => &jump::Axel::INFO,
+            Self::Euler(_count) => &jump::Euler::INFO,
+            Self::JumpCombo(_combo) => &jump::Combo::INFO,
+            Self::WaltzJump => &jump::WaltzJump::INFO,
+            Self::HalfFlip => &jump::HalfFlip::INFO,
+            Self::HalfLutz => &jump::HalfLutz::INFO,
+            Self::SplitJump => &jump::SplitJump::INFO,
+            Self::StagJump => &jump::StagJump::INFO,
+            Self::Walley => &jump::Walley::INFO,
+            Self::ToeWalley => &jump::ToeWalley::INFO,
+            Self::BunnyHop => &jump::BunnyHop::INFO,
+            Self::Spin(_kind) => &spin::Spin::INFO,
+            Self::Figure => &figure::Figure::INFO,
+        }
+    }
+    /// Return the identifier for a single jump.
+    pub fn jump(kind: JumpKind, count: JumpCount) -> Self {
+        match kind {
+            JumpKind::Salchow => Self::Salchow(count),
+            JumpKind::ToeLoop => Self::ToeLoop(count),
+            JumpKind::Loop => Self::LoopJump(count),
+            JumpKind::Flip => Self::Flip(count),
+            JumpKind::Lutz => Self::Lutz(count),
+            JumpKind::Axel => Self::Axel(count),
+            JumpKind::Euler => Self::Euler(count),
+        }
+    }
+    /// Return the kind and rotation count if this is a single jump.
+    pub fn as_jump(&self) -> Option<(JumpKind, JumpCount)> {
+        match self {
+            Self::Salchow(count) => Some((JumpKind::Salchow, *count)),
+            Self::ToeLoop(count) => Some((JumpKind::ToeLoop, *count)),
+            Self::LoopJump(count) => Some((JumpKind::Loop, *count)),
+            Self::Flip(count) => Some((JumpKind::Flip, *count)),
+            Self::Lutz(count) => Some((JumpKind::Lutz, *count)),
+            Self::Axel(count) => Some((JumpKind::Axel, *count)),
+            Self::Euler(count) => Some((JumpKind::Euler, *count)),
+            _ => None,
         }
     }
     /// Construct an instance of a skating move.
@@ -246,6 +332,18 @@ impl SkatingMoveId {
             Self::Flip(count) => make_move!(jump::Flip, *count),
             Self::Lutz(count) => make_move!(jump::Lutz, *count),
             Self::Axel(count) => make_move!(jump::Axel, *count),
+            Self::Euler(count) => make_move!(jump::Euler, *count),
+            Self::JumpCombo(combo) => make_move!(jump::Combo, *combo),
+            Self::WaltzJump => make_move!(jump::WaltzJump),
+            Self::HalfFlip => make_move!(jump::HalfFlip),
+            Self::HalfLutz => make_move!(jump::HalfLutz),
+            Self::SplitJump => make_move!(jump::SplitJump),
+            Self::StagJump => make_move!(jump::StagJump),
+            Self::Walley => make_move!(jump::Walley),
+            Self::ToeWalley => make_move!(jump::ToeWalley),
+            Self::BunnyHop => make_move!(jump::BunnyHop),
+            Self::Spin(kind) => make_move!(spin::Spin, *kind),
+            Self::Figure => make_move!(figure::Figure),
         })
     }
 }
@@ -259,6 +357,8 @@ pub enum PseudoMoveId {
     Shift,
     /// Rink
     Rink,
+    /// Dance axis or barrier baseline
+    Axis,
     /// Info
     Info,
     /// Title
@@ -267,10 +367,26 @@ pub enum PseudoMoveId {
     Text,
     /// Label
     Label,
+    /// Switch skater
+    Skater,
+    /// Partner track
+    Partner,
+    /// Synchronized skating formation
+    Formation,
     /// Start repeating section.
     RepeatStart,
     /// End repeating section.
     RepeatEnd,
+    /// Start definition of named sequence.
+    Define,
+    /// End definition of named sequence.
+    DefineEnd,
+    /// Perform named sequence.
+    Call,
+    /// Include moves from another file.
+    Include,
+    /// Set variable.
+    Let,
 }
 
 impl PseudoMoveId {
@@ -280,12 +396,21 @@ impl PseudoMoveId {
             Self::Warp => &warp::Warp::INFO,
             Self::Shift => &shift::Shift::INFO,
             Self::Rink => &rink::Rink::INFO,
+            Self::Axis => &axis::Axis::INFO,
             Self::Info => &info::Info::INFO,
             Self::Title => &title::Title::INFO,
             Self::Text => &text::Text::INFO,
             Self::Label => &label::Label::INFO,
+            Self::Skater => &skater::Skater::INFO,
+            Self::Partner => &partner::Partner::INFO,
+            Self::Formation => &formation::Formation::INFO,
             Self::RepeatStart => &repeat::RepeatStart::INFO,
             Self::RepeatEnd => &repeat::RepeatEnd::INFO,
+            Self::Define => &define::Define::INFO,
+            Self::DefineEnd => &define::DefineEnd::INFO,
+            Self::Call => &define::Call::INFO,
+            Self::Include => &include::Include::INFO,
+            Self::Let => &variable::Let::INFO,
         }
     }
 
@@ -301,12 +426,25 @@ impl PseudoMoveId {
             Self::Warp => Box::new(warp::Warp::from_params(text_pos, params)?),
             Self::Shift => Box::new(shift::Shift::from_params(text_pos, params)?),
             Self::Rink => Box::new(rink::Rink::from_params(text_pos, params)?),
+            Self::Axis => Box::new(axis::Axis::from_params(text_pos, params)?),
             Self::Info => Box::new(info::Info::from_params(text_pos, params)?),
             Self::Title => Box::new(title::Title::from_params(text_pos, params)?),
             Self::Text => Box::new(text::Text::from_params(text_pos, params)?),
             Self::Label => Box::new(label::Label::from_params(text_pos, params)?),
+            Self::Skater => Box::new(skater::Skater::from_params(text_pos, params)?),
+            Self::Partner => Box::new(partner::Partner::from_params(text_pos, params)?),
+            Self::Formation => Box::new(formation::Formation::from_params(text_pos, params)?),
             Self::RepeatStart => Box::new(repeat::RepeatStart::from_params(text_pos, params)?),
             Self::RepeatEnd => Box::new(repeat::RepeatEnd::from_params(text_pos, params)?),
+            Self::Define => Box::new(define::Define::from_params(text_pos, params)?),
+            Self::DefineEnd => Box::new(define::DefineEnd::from_params(text_pos, params)?),
+            Self::Call => Box::new(define::Call::from_params(text_pos, params)?),
+            Self::Include => Box::new(include::Include::from_params(text_pos, params, &ctx.vars)?),
+            Self::Let => {
+                let mv = variable::Let::from_params(text_pos, params)?;
+                ctx.vars.insert(mv.name.clone(), mv.value);
+                Box::new(mv)
+            }
         })
     }
 }
@@ -335,6 +473,8 @@ impl MoveId {
 pub(crate) struct Context {
     /// Final label of the previous move.
     pub prev_label: Option<String>,
+    /// Values of variables defined so far.
+    pub vars: HashMap<String, i32>,
 }
 
 impl Context {
@@ -480,8 +620,44 @@ fn standard_transition(from: Code, to: Code, half_width: Centimetres) -> Transit
     }
 }
 
+/// Return the warning (if any) for continuing from `from` to the start `to` of the next move without any turn, when
+/// checking strictly.  Stepping onto the other foot (or to or from two feet) allows any change of direction or edge,
+/// but staying on the same foot does not.
+pub fn continuity_warning(from: Code, to: Code) -> Option<WarningKind> {
+    if from.foot != to.foot || from.foot == Foot::Both {
+        return None;
+    }
+    if from.dir != to.dir {
+        Some(WarningKind::SameFootDirectionChange { from, to })
+    } else if from.edge != to.edge && from.edge != Edge::Flat && to.edge != Edge::Flat {
+        // Flattening out an edge is fine.
+        Some(WarningKind::SameFootEdgeChange { from, to })
+    } else {
+        None
+    }
+}
+
+/// Return the warning (if any) for a pre-transition with feet crossing over.
+pub fn cross_transition_warning(from: Code, to: Code) -> Option<WarningKind> {
+    if from.dir != to.dir {
+        // Falls back to the standard transition.
+        return None;
+    }
+    match (from.foot, to.foot) {
+        (Foot::Left, Foot::Left) | (Foot::Right, Foot::Right) => {
+            Some(WarningKind::CrossNoFootChange)
+        }
+        (Foot::Both, _) => Some(WarningKind::CrossFromTwoFeet),
+        (_, Foot::Both) => Some(WarningKind::CrossToTwoFeet),
+        (Foot::Left, Foot::Right) | (Foot::Right, Foot::Left) => None,
+    }
+}
+
 /// Pre-transition with feet crossing over.
 pub fn cross_transition(from: Code, to: Code) -> Transition {
+    if let Some(kind) = cross_transition_warning(from, to) {
+        warn!("{kind} ({from}->{to})!");
+    }
     let mut x = Centimetres(0);
     let mut y = Centimetres(0);
     match (from.dir, to.dir) {
@@ -496,20 +672,9 @@ pub fn cross_transition(from: Code, to: Code) -> Transition {
                     x = -HW;
                     y = SL / 2;
                 }
-                (Foot::Left, Foot::Left) | (Foot::Right, Foot::Right) => {
-                    warn!("XF transition but no foot change ({from}->{to})!");
-                }
-                (Foot::Both, _) => {
-                    warn!("XF transition from two feet ({from}->{to})!");
-                }
-                (Foot::Left, Foot::Both) => {
-                    warn!("XF transition to two feet ({from}->{to})!");
-                    x = -HW;
-                }
-                (Foot::Right, Foot::Both) => {
-                    warn!("XF transition to two feet ({from}->{to})!");
-                    x = HW;
-                }
+                (Foot::Left, Foot::Left) | (Foot::Right, Foot::Right) | (Foot::Both, _) => {}
+                (Foot::Left, Foot::Both) => x = -HW,
+                (Foot::Right, Foot::Both) => x = HW,
             }
         }
         (Backward, Backward) => {
@@ -523,20 +688,9 @@ pub fn cross_transition(from: Code, to: Code) -> Transition {
                     x = SL / 2;
                     y = HW
                 }
-                (Foot::Left, Foot::Left) | (Foot::Right, Foot::Right) => {
-                    warn!("XB transition but no foot change ({from}->{to})!");
-                }
-                (Foot::Both, _) => {
-                    warn!("XB transition from two feet ({from}->{to})!");
-                }
-                (Foot::Left, Foot::Both) => {
-                    warn!("XB transition to two feet ({from}->{to})!");
-                    x = HW;
-                }
-                (Foot::Right, Foot::Both) => {
-                    warn!("XB transition to two feet ({from}->{to})!");
-                    x = -HW;
-                }
+                (Foot::Left, Foot::Left) | (Foot::Right, Foot::Right) | (Foot::Both, _) => {}
+                (Foot::Left, Foot::Both) => x = HW,
+                (Foot::Right, Foot::Both) => x = -HW,
             }
         }
         // A cross transition that changes skating direction doesn't make much sense, so fall back to the standard
@@ -0,0 +1,233 @@
+// Copyright 2025 David Drysdale
+
+//! Pseudo-move definition for a dance axis or a baseline relative to the barrier.
+
+use crate::{
+    code,
+    moves::{self, rink::Rink, MoveId, PseudoMoveId},
+    param, params,
+    params::Value,
+    path, Bounds, Centimetres, Direction, Document, Move, MoveParam, ParseError, Position,
+    RenderOptions, Skater, SpatialTransition, SvgId, TextPosition, Transition,
+};
+
+/// Direction of travel along the long axis of an unrotated portrait rink.
+const PORTRAIT_DIR: i32 = 0;
+/// Direction of travel along the long axis of an unrotated landscape rink.
+const LANDSCAPE_DIR: i32 = 270;
+
+#[derive(Debug, Clone)]
+pub struct Axis {
+    text_pos: TextPosition,
+    barrier: Centimetres,
+    along: Centimetres,
+    reverse: bool,
+    start: bool,
+    show: bool,
+    /// Rink that the axis is drawn on; a standard rink until placed on the rink in force with [`Axis::on`].
+    rink: Rink,
+}
+
+impl Axis {
+    pub const MOVE: &'static str = "Axis";
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::Axis),
+        summary: "Dance axis or barrier baseline on the rink",
+        example: "Axis[barrier=500,along=1000,start=true]",
+        visible: false,
+        params: &[
+            params::Info {
+                name: "barrier",
+                doc: "Distance of the line from the barrier in centimetres, measured from the left (or top) barrier if positive and from the right (or bottom) barrier if negative; 0 for the long axis of the rink",
+                default: Value::Number(0),
+                range: params::Range::Any,
+                short: None,
+            },
+            params::Info {
+                name: "along",
+                doc: "Distance along the line from the top (or left) end of the rink of the point to start at, in centimetres",
+                default: Value::Number(0),
+                range: params::Range::Positive,
+                short: None,
+            },
+            params::Info {
+                name: "reverse",
+                doc: "Whether to face towards the top (or left) end of the rink when starting on the line",
+                default: Value::Boolean(false),
+                range: params::Range::Boolean,
+                short: None,
+            },
+            params::Info {
+                name: "start",
+                doc: "Whether to move the skater onto the line, facing along it",
+                default: Value::Boolean(false),
+                range: params::Range::Boolean,
+                short: None,
+            },
+            params::Info {
+                name: "show",
+                doc: "Whether to draw the line",
+                default: Value::Boolean(true),
+                range: params::Range::Boolean,
+                short: None,
+            },
+        ],
+    };
+
+    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        Ok(Self {
+            text_pos,
+            barrier: params[0].value.as_cm(text_pos)?,
+            along: params[1].value.as_cm(text_pos)?,
+            reverse: params[2].value.as_bool(text_pos)?,
+            start: params[3].value.as_bool(text_pos)?,
+            show: params[4].value.as_bool(text_pos)?,
+            rink: Rink::standard(text_pos),
+        })
+    }
+
+    /// Return a copy of the axis that is placed on the given `rink`.
+    pub fn on(&self, rink: &Rink) -> Self {
+        Self {
+            rink: rink.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Convert a position given as a distance `across` the rink from the left (or top) barrier and a distance `along`
+    /// the rink from the top (or left) end into diagram coordinates.
+    fn at(&self, across: Centimetres, along: Centimetres) -> Position {
+        let delta = if self.rink.portrait() {
+            Position {
+                x: across,
+                y: along,
+            }
+        } else {
+            Position {
+                x: along,
+                y: across,
+            }
+        };
+        let corner = self.rink.corner(code!(BF));
+        corner.pos.add_rotated(corner.dir, delta)
+    }
+
+    /// Distance of the line across the rink, from the left (or top) barrier.
+    fn across(&self) -> Centimetres {
+        let (width, length) = self.rink.size();
+        let short = std::cmp::min(width, length);
+        match self.barrier.0 {
+            0 => short / 2,
+            dist if dist > 0 => self.barrier,
+            _ => short + self.barrier,
+        }
+    }
+
+    /// End points of the line, at either end of the rink.
+    fn ends(&self) -> (Position, Position) {
+        let (width, length) = self.rink.size();
+        let long = std::cmp::max(width, length);
+        let across = self.across();
+        (self.at(across, Centimetres(0)), self.at(across, long))
+    }
+
+    /// Direction of travel when starting on the line.
+    fn dir(&self) -> Direction {
+        let base = if self.rink.portrait() {
+            PORTRAIT_DIR
+        } else {
+            LANDSCAPE_DIR
+        };
+        let reverse = if self.reverse { 180 } else { 0 };
+        let corner = self.rink.corner(code!(BF));
+        Direction::new(base + reverse + corner.dir.0 as i32)
+    }
+}
+
+impl Move for Axis {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::Axis)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![
+            param!("barrier" = (self.barrier.0 as i32)),
+            param!("along" = (self.along.0 as i32)),
+            param!(self.reverse),
+            param!(self.start),
+            param!(self.show),
+        ]
+    }
+    fn text(&self) -> String {
+        let params = params::to_string(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn expanded_text(&self) -> String {
+        let params = params::to_expanded(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn transition(&self) -> Transition {
+        if !self.start {
+            return Transition::default();
+        }
+        Transition {
+            spatial: SpatialTransition::Absolute {
+                pos: self.at(self.across(), self.along),
+                dir: self.dir(),
+            },
+            code: None,
+        }
+    }
+    fn bounds(&self, before: &Skater) -> Option<Bounds> {
+        let mut bounds = Bounds {
+            top_left: before.pos,
+            bottom_right: before.pos,
+        };
+        if self.show {
+            let (from, to) = self.ends();
+            bounds.encompass(&from);
+            bounds.encompass(&to);
+        }
+        let after = *before + self.transition();
+        bounds.encompass(&after.pos);
+        Some(bounds)
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &Skater,
+        opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        if !self.show {
+            return doc;
+        }
+        let (from, to) = self.ends();
+        let mut line = path!("M {},{} L {},{}", from.x.0, from.y.0, to.x.0, to.y.0)
+            .set("stroke-dasharray", "40,20")
+            .set(
+                "style",
+                format!("stroke:grey; stroke-width:{};", opts.stroke_width().0),
+            );
+        if let Some(pos) = self.text_pos() {
+            let unique_id = opts.next_unique_id(pos);
+            line = line.set("id", unique_id);
+        }
+        doc.add(line)
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        self.box_clone(repeat)
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+    fn as_axis(&self) -> Option<&Axis> {
+        Some(self)
+    }
+}
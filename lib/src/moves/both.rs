@@ -6,14 +6,14 @@ use crate::{
     apply_style, code,
     moves::{
         self,
-        edge::{endpoint, percent_point, radius, timing_text},
+        edge::{endpoint, percent_point, radius, timing_text, PATH_SAMPLES},
         MoveId, SkatingMoveId, HW,
     },
     param, params,
     params::Value,
     path, pos, Bounds, Centimetres, Code, Label, Move, MoveParam, ParseError, Percentage, Position,
     PreTransition, RenderOptions, Rotation, RotationDirection, Skater, SpatialTransition, SvgId,
-    TextPosition, Transition,
+    TextPosition, Transition, WarningKind,
 };
 use std::borrow::Cow;
 use svg::node::element::Group;
@@ -125,6 +125,8 @@ impl CurveBoth {
             return Err(ParseError {
                 pos: text_pos,
                 msg: format!("{entry_code} not supported"),
+                file: None,
+                calls: Vec::new(),
             });
         }
 
@@ -237,6 +239,11 @@ impl Move for CurveBoth {
     fn text_pos(&self) -> Option<TextPosition> {
         Some(self.text_pos)
     }
+    fn path_points(&self) -> Vec<Position> {
+        (0..=PATH_SAMPLES)
+            .map(|n| self.percent_point(Percentage(n * 100 / PATH_SAMPLES)))
+            .collect()
+    }
     fn pre_transition(&self, from: Code) -> Transition {
         if let Some(start) = self.start() {
             self.pre_transition.perform(from, start)
@@ -244,6 +251,10 @@ impl Move for CurveBoth {
             Transition::default()
         }
     }
+    fn pre_transition_warning(&self, from: Code, strict: bool) -> Option<WarningKind> {
+        self.start()
+            .and_then(|start| self.pre_transition.warning(from, start, strict))
+    }
     fn transition(&self) -> Transition {
         Transition {
             spatial: SpatialTransition::Relative {
@@ -332,14 +343,14 @@ impl Move for CurveBoth {
             (distance * half_theta.sin()) as i64
         );
 
-        let lf_text = match &self.left_label {
+        let lf_text = opts.label_text(match &self.left_label {
             Some(label) => label.to_string(),
             None => format!("{}", self.left_code()),
-        };
-        let rf_text = match &self.right_label {
+        });
+        let rf_text = opts.label_text(match &self.right_label {
             Some(label) => label.to_string(),
             None => format!("{}", self.right_code()),
-        };
+        });
         let display =
             opts.count.is_some() || !lf_text.trim().is_empty() || !rf_text.trim().is_empty();
 
@@ -5,8 +5,8 @@ use crate::{
     moves::{MoveId, SkatingMoveId},
     params,
     params::Value,
-    Bounds, Centimetres, Code, Label, Move, MoveParam, ParseError, RenderOptions, Rotation, Skater,
-    SpatialTransition, SvgId, TextPosition, Transition,
+    Bounds, Centimetres, Code, Label, Move, MoveParam, ParseError, Position, RenderOptions,
+    Rotation, Skater, SpatialTransition, SvgId, TextPosition, Transition, WarningKind,
 };
 use std::borrow::Cow;
 use std::fmt;
@@ -113,6 +113,11 @@ impl Compound {
         }
     }
 
+    /// Consume the compound move, returning its constituent moves.
+    pub fn into_moves(self) -> Vec<Box<dyn Move>> {
+        self.moves
+    }
+
     fn for_each_move<F>(&self, op: F)
     where
         F: FnMut(&Skater, usize, &Box<dyn Move>),
@@ -156,9 +161,23 @@ impl Move for Compound {
     fn text_pos(&self) -> Option<TextPosition> {
         Some(self.text_pos)
     }
+    fn path_points(&self) -> Vec<Position> {
+        let mut points = Vec::new();
+        self.for_each_move(|skater, _idx, mv| {
+            points.extend(
+                mv.path_points()
+                    .into_iter()
+                    .map(|pt| skater.pos.add_rotated(skater.dir, pt)),
+            );
+        });
+        points
+    }
     fn pre_transition(&self, from: Code) -> Transition {
         self.moves[0].pre_transition(from)
     }
+    fn pre_transition_warning(&self, from: Code, strict: bool) -> Option<WarningKind> {
+        self.moves[0].pre_transition_warning(from, strict)
+    }
     fn transition(&self) -> Transition {
         let mut skater = Skater::at_zero(self.start_code);
         for mv in &self.moves {
@@ -507,6 +526,8 @@ pub fn add_angle(
         Err(ParseError {
             pos,
             msg: format!("Delta-angle value {delta_angle} must be smaller than angle {angle1}"),
+            file: None,
+            calls: Vec::new(),
         })
     } else {
         Ok(angle2)
@@ -524,6 +545,8 @@ pub fn add_len(
         Err(ParseError {
             pos,
             msg: format!("Delta-len value {delta_len} must be smaller than len {len1}"),
+            file: None,
+            calls: Vec::new(),
         })
     } else {
         Ok(len2)
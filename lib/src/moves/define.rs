@@ -0,0 +1,252 @@
+// Copyright 2025 David Drysdale
+
+//! Pseudo-move definitions for named sequences of moves.
+
+use crate::{
+    moves::{self, MoveId, PseudoMoveId},
+    param, params,
+    params::Value,
+    Bounds, Document, Move, MoveParam, ParseError, RenderOptions, Skater, SvgId, TextPosition,
+};
+use std::borrow::Cow;
+
+/// Extract a sequence name from parameters, which must not be empty.
+fn name_from(text_pos: TextPosition, value: &Value, mv: &str) -> Result<String, ParseError> {
+    let name = value.as_str(text_pos)?;
+    if name.is_empty() {
+        return Err(ParseError {
+            pos: text_pos,
+            msg: format!("{mv} needs a non-empty name"),
+            file: None,
+            calls: Vec::new(),
+        });
+    }
+    Ok(name.to_string())
+}
+
+#[derive(Debug, Clone)]
+pub struct Define {
+    text_pos: TextPosition,
+    pub name: String,
+}
+
+impl Define {
+    pub const MOVE: &'static str = "Define";
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::Define),
+        summary: "Mark start of a named sequence of moves",
+        example: "Define[name=\"prog\"]",
+        visible: false,
+        params: &[params::Info {
+            name: "name",
+            doc: "Name of the sequence",
+            default: Value::Text(Cow::Borrowed("")),
+            range: params::Range::Text,
+            short: None,
+        }],
+    };
+
+    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        Ok(Self {
+            text_pos,
+            name: name_from(text_pos, &params[0].value, Self::MOVE)?,
+        })
+    }
+}
+
+impl Move for Define {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::Define)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![param!(self.name)]
+    }
+    fn text(&self) -> String {
+        let params = params::to_string(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn expanded_text(&self) -> String {
+        let params = params::to_expanded(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn bounds(&self, _before: &Skater) -> Option<Bounds> {
+        None
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &Skater,
+        _opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        doc
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        self.box_clone(repeat)
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+    fn as_define(&self) -> Option<&Define> {
+        Some(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DefineEnd {
+    text_pos: TextPosition,
+}
+
+impl DefineEnd {
+    pub const MOVE: &'static str = "End";
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::DefineEnd),
+        summary: "Mark end of a named sequence of moves",
+        example: Self::MOVE,
+        visible: false,
+        params: &[],
+    };
+
+    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        Ok(Self { text_pos })
+    }
+}
+
+impl Move for DefineEnd {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::DefineEnd)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![]
+    }
+    fn text(&self) -> String {
+        Self::MOVE.to_string()
+    }
+    fn expanded_text(&self) -> String {
+        self.text()
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn bounds(&self, _before: &Skater) -> Option<Bounds> {
+        None
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &Skater,
+        _opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        doc
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        self.box_clone(repeat)
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Call {
+    text_pos: TextPosition,
+    pub name: String,
+    pub opposite: bool,
+}
+
+impl Call {
+    pub const MOVE: &'static str = "Call";
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::Call),
+        summary: "Perform a previously defined sequence of moves",
+        example: "Call[name=\"prog\",opposite=true]",
+        visible: false,
+        params: &[
+            params::Info {
+                name: "name",
+                doc: "Name of the sequence to perform",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
+            params::Info {
+                name: "opposite",
+                doc: "Whether to perform the sequence on the opposite feet",
+                default: Value::Boolean(false),
+                range: params::Range::Boolean,
+                short: None,
+            },
+        ],
+    };
+
+    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        Ok(Self {
+            text_pos,
+            name: name_from(text_pos, &params[0].value, Self::MOVE)?,
+            opposite: params[1].value.as_bool(text_pos)?,
+        })
+    }
+}
+
+impl Move for Call {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::Call)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![param!(self.name), param!(self.opposite)]
+    }
+    fn text(&self) -> String {
+        let params = params::to_string(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn expanded_text(&self) -> String {
+        let params = params::to_expanded(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn bounds(&self, _before: &Skater) -> Option<Bounds> {
+        None
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &Skater,
+        _opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        doc
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        copy.opposite = !self.opposite;
+        Box::new(copy)
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+    fn as_call(&self) -> Option<&Call> {
+        Some(self)
+    }
+}
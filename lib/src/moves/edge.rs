@@ -9,7 +9,7 @@ use crate::{
     params::Value,
     path, pos, Bounds, Centimetres, Code, Label, Move, MoveParam, ParseError, Percentage, Position,
     PreTransition, RenderOptions, Rotation, RotationDirection, Skater, SpatialTransition, SvgId,
-    TextPosition, Transition,
+    TextPosition, Transition, WarningKind,
 };
 use std::borrow::Cow;
 use std::f64::consts::PI;
@@ -18,6 +18,9 @@ use svg::node::element::TSpan as SvgTSpan;
 use svg::node::element::Text as SvgText;
 use svg::node::Text as NodeText;
 
+/// Number of segments that a curve is divided into when sampling points along it.
+pub(crate) const PATH_SAMPLES: i32 = 10;
+
 #[derive(Debug, Clone)]
 pub struct Curve {
     text_pos: TextPosition,
@@ -136,6 +139,8 @@ impl Curve {
             return Err(ParseError {
                 pos: text_pos,
                 msg: format!("{entry_code} not supported"),
+                file: None,
+                calls: Vec::new(),
             });
         }
 
@@ -168,11 +173,9 @@ impl Curve {
 
     /// Direction of increasing angle.
     fn sign(&self) -> RotationDirection {
-        match &self.code {
-            code!(LFO) | code!(RFI) | code!(LBI) | code!(RBO) => RotationDirection::AntiClockwise,
-            code!(RFO) | code!(LFI) | code!(RBI) | code!(LBO) => RotationDirection::Clockwise,
-            _ => unreachable!("sign for {:?} hit despite constructor check", self.code),
-        }
+        self.code.curve_direction().unwrap_or_else(|| {
+            unreachable!("sign for {:?} hit despite constructor check", self.code)
+        })
     }
 
     /// Radius of the circle for which this is an arc.
@@ -221,6 +224,11 @@ impl Move for Curve {
     fn text_pos(&self) -> Option<TextPosition> {
         Some(self.text_pos)
     }
+    fn path_points(&self) -> Vec<Position> {
+        (0..=PATH_SAMPLES)
+            .map(|n| self.percent_point(Percentage(n * 100 / PATH_SAMPLES)))
+            .collect()
+    }
     fn pre_transition(&self, from: Code) -> Transition {
         if let Some(start) = self.start() {
             self.pre_transition.perform(from, start)
@@ -228,6 +236,10 @@ impl Move for Curve {
             Transition::default()
         }
     }
+    fn pre_transition_warning(&self, from: Code, strict: bool) -> Option<WarningKind> {
+        self.start()
+            .and_then(|start| self.pre_transition.warning(from, start, strict))
+    }
     fn transition(&self) -> Transition {
         Transition {
             spatial: SpatialTransition::Relative {
@@ -291,10 +303,10 @@ impl Move for Curve {
             (distance * half_theta.sin()) as i64
         );
 
-        let text = match &self.label {
+        let text = opts.label_text(match &self.label {
             Some(label) => label.to_string(),
             None => format!("{}", self.code),
-        };
+        });
         let display = opts.count.is_some() || !text.trim().is_empty();
 
         let svg_text = if let Some(count) = opts.count {
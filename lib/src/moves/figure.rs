@@ -0,0 +1,703 @@
+// Copyright 2025 David Drysdale
+
+//! Compulsory (school) figures.
+//!
+//! Each figure is built up from circles that start and finish at the centre of the figure.  Turns on a circle are
+//! spread evenly around it, and a change of foot at the centre is shown as a push.
+
+use super::{
+    compound::Compound, edge::Curve, edge_err, info, label::Label, shift::Shift, MoveId,
+    SkatingMoveId,
+};
+use crate::{
+    code, moves, params, params::Value, Centimetres, Code, Move, MoveParam, ParseError, Position,
+    PreTransition, RenderOptions, Rotation, RotationDirection, SpatialTransition, SvgId,
+    TextPosition, Transition, WarningKind,
+};
+use std::borrow::Cow;
+use std::f64::consts::PI;
+use svg::node::element::Group;
+
+/// Standard diameter of the circles of a figure, in centimetres.
+const CIRCLE_DIAMETER: i32 = 600;
+/// Standard diameter of the circles of a loop figure, in centimetres.
+const LOOP_CIRCLE_DIAMETER: i32 = 400;
+/// Angle on either side of a three turn or bracket that is taken up by the turn, in degrees.
+const TURN_SPREAD: f64 = 15.0;
+/// Distance from the circle to the point of a three turn or bracket, as a fraction of the radius.
+const TURN_DEPTH: f64 = 0.1;
+/// Size of a loop, as a fraction of the radius.
+const LOOP_SIZE: f64 = 0.25;
+/// Sideways distance of a turn label from the point of the turn, in centimetres.
+const TURN_LABEL_OFFSET: i32 = 40;
+
+/// Turn made part-way around a circle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    Three,
+    Bracket,
+    Loop,
+}
+
+impl Turn {
+    /// Label to show next to the turn.
+    fn label(&self) -> &'static str {
+        match self {
+            Turn::Three => "3",
+            Turn::Bracket => "Br",
+            Turn::Loop => "",
+        }
+    }
+
+    /// Edge after the turn.
+    fn exit(&self, code: Code) -> Code {
+        match self {
+            Turn::Three | Turn::Bracket => Code {
+                foot: code.foot,
+                dir: code.dir.opposite(),
+                edge: code.edge.opposite(),
+            },
+            Turn::Loop => code,
+        }
+    }
+}
+
+/// Circle (or half circle) of a figure, skated on one foot.
+#[derive(Debug, Clone, Copy)]
+struct Lobe {
+    /// Edge at the start of the circle.
+    code: Code,
+    /// Turn made on the circle, with the number of times it is made.
+    turns: Option<(Turn, usize)>,
+    /// Whether only half of the circle is skated, before a change of edge.
+    half: bool,
+}
+
+const fn circle(code: Code) -> Lobe {
+    Lobe {
+        code,
+        turns: None,
+        half: false,
+    }
+}
+
+const fn half(code: Code) -> Lobe {
+    Lobe {
+        code,
+        turns: None,
+        half: true,
+    }
+}
+
+const fn turns(code: Code, turn: Turn, count: usize) -> Lobe {
+    Lobe {
+        code,
+        turns: Some((turn, count)),
+        half: false,
+    }
+}
+
+/// Description of a school figure.
+struct Schedule {
+    /// ISU number of the figure.
+    number: &'static str,
+    /// Name of the figure.
+    name: &'static str,
+    /// Circles that make up a single tracing of the figure.
+    lobes: &'static [Lobe],
+}
+
+impl Schedule {
+    /// Standard diameter of the circles for the figure.
+    fn diameter(&self) -> i32 {
+        if self
+            .lobes
+            .iter()
+            .any(|lobe| matches!(lobe.turns, Some((Turn::Loop, _))))
+        {
+            LOOP_CIRCLE_DIAMETER
+        } else {
+            CIRCLE_DIAMETER
+        }
+    }
+}
+
+macro_rules! figure {
+    { $number:literal, $name:literal, [$($lobe:expr),+ $(,)?] } => {
+        Schedule {
+            number: $number,
+            name: $name,
+            lobes: &[$($lobe),+],
+        }
+    };
+}
+
+/// Available school figures.
+const FIGURES: &[Schedule] = &[
+    figure!(
+        "1",
+        "Circle Eight",
+        [circle(code!(RFO)), circle(code!(LFO))]
+    ),
+    figure!(
+        "2",
+        "Circle Eight",
+        [circle(code!(RFI)), circle(code!(LFI))]
+    ),
+    figure!(
+        "3",
+        "Circle Eight",
+        [circle(code!(RBO)), circle(code!(LBO))]
+    ),
+    figure!(
+        "4",
+        "Circle Eight",
+        [circle(code!(RBI)), circle(code!(LBI))]
+    ),
+    figure!(
+        "5a",
+        "Serpentine",
+        [
+            circle(code!(RFO)),
+            half(code!(RFI)),
+            circle(code!(LFI)),
+            half(code!(LFO))
+        ]
+    ),
+    figure!(
+        "5b",
+        "Serpentine",
+        [
+            circle(code!(LFO)),
+            half(code!(LFI)),
+            circle(code!(RFI)),
+            half(code!(RFO))
+        ]
+    ),
+    figure!(
+        "6a",
+        "Serpentine",
+        [
+            circle(code!(RBO)),
+            half(code!(RBI)),
+            circle(code!(LBI)),
+            half(code!(LBO))
+        ]
+    ),
+    figure!(
+        "6b",
+        "Serpentine",
+        [
+            circle(code!(LBO)),
+            half(code!(LBI)),
+            circle(code!(RBI)),
+            half(code!(RBO))
+        ]
+    ),
+    figure!(
+        "7",
+        "Three",
+        [
+            turns(code!(RFO), Turn::Three, 1),
+            turns(code!(LBI), Turn::Three, 1)
+        ]
+    ),
+    figure!(
+        "8a",
+        "Three",
+        [
+            turns(code!(RFI), Turn::Three, 1),
+            turns(code!(LBO), Turn::Three, 1)
+        ]
+    ),
+    figure!(
+        "8b",
+        "Three",
+        [
+            turns(code!(LFI), Turn::Three, 1),
+            turns(code!(RBO), Turn::Three, 1)
+        ]
+    ),
+    figure!(
+        "10a",
+        "Double Three",
+        [
+            turns(code!(RFO), Turn::Three, 2),
+            turns(code!(LFO), Turn::Three, 2)
+        ]
+    ),
+    figure!(
+        "10b",
+        "Double Three",
+        [
+            turns(code!(LFO), Turn::Three, 2),
+            turns(code!(RFO), Turn::Three, 2)
+        ]
+    ),
+    figure!(
+        "14",
+        "Loop",
+        [
+            turns(code!(RFO), Turn::Loop, 1),
+            turns(code!(LFO), Turn::Loop, 1)
+        ]
+    ),
+    figure!(
+        "15",
+        "Loop",
+        [
+            turns(code!(RFI), Turn::Loop, 1),
+            turns(code!(LFI), Turn::Loop, 1)
+        ]
+    ),
+    figure!(
+        "16",
+        "Loop",
+        [
+            turns(code!(RBO), Turn::Loop, 1),
+            turns(code!(LBO), Turn::Loop, 1)
+        ]
+    ),
+    figure!(
+        "17",
+        "Loop",
+        [
+            turns(code!(RBI), Turn::Loop, 1),
+            turns(code!(LBI), Turn::Loop, 1)
+        ]
+    ),
+    figure!(
+        "18a",
+        "Bracket",
+        [
+            turns(code!(RFO), Turn::Bracket, 1),
+            turns(code!(LBI), Turn::Bracket, 1)
+        ]
+    ),
+    figure!(
+        "18b",
+        "Bracket",
+        [
+            turns(code!(LFO), Turn::Bracket, 1),
+            turns(code!(RBI), Turn::Bracket, 1)
+        ]
+    ),
+    figure!(
+        "19a",
+        "Bracket",
+        [
+            turns(code!(RFI), Turn::Bracket, 1),
+            turns(code!(LBO), Turn::Bracket, 1)
+        ]
+    ),
+    figure!(
+        "19b",
+        "Bracket",
+        [
+            turns(code!(LFI), Turn::Bracket, 1),
+            turns(code!(RBO), Turn::Bracket, 1)
+        ]
+    ),
+    figure!(
+        "31a",
+        "Paragraph Double Three",
+        [
+            turns(code!(RFO), Turn::Three, 2),
+            turns(code!(RFI), Turn::Three, 2),
+            turns(code!(LFO), Turn::Three, 2),
+            turns(code!(LFI), Turn::Three, 2)
+        ]
+    ),
+    figure!(
+        "31b",
+        "Paragraph Double Three",
+        [
+            turns(code!(LFO), Turn::Three, 2),
+            turns(code!(LFI), Turn::Three, 2),
+            turns(code!(RFO), Turn::Three, 2),
+            turns(code!(RFI), Turn::Three, 2)
+        ]
+    ),
+    figure!(
+        "33a",
+        "Paragraph Loop",
+        [
+            turns(code!(RFO), Turn::Loop, 1),
+            turns(code!(RFI), Turn::Loop, 1),
+            turns(code!(LFO), Turn::Loop, 1),
+            turns(code!(LFI), Turn::Loop, 1)
+        ]
+    ),
+    figure!(
+        "33b",
+        "Paragraph Loop",
+        [
+            turns(code!(LFO), Turn::Loop, 1),
+            turns(code!(LFI), Turn::Loop, 1),
+            turns(code!(RFO), Turn::Loop, 1),
+            turns(code!(RFI), Turn::Loop, 1)
+        ]
+    ),
+    figure!(
+        "39a",
+        "Paragraph Bracket",
+        [
+            turns(code!(RFO), Turn::Bracket, 1),
+            turns(code!(RBO), Turn::Bracket, 1),
+            turns(code!(LFO), Turn::Bracket, 1),
+            turns(code!(LBO), Turn::Bracket, 1)
+        ]
+    ),
+    figure!(
+        "39b",
+        "Paragraph Bracket",
+        [
+            turns(code!(LFO), Turn::Bracket, 1),
+            turns(code!(LBO), Turn::Bracket, 1),
+            turns(code!(RFO), Turn::Bracket, 1),
+            turns(code!(RBO), Turn::Bracket, 1)
+        ]
+    ),
+];
+
+/// Numbers of the available school figures, in the same order as [`FIGURES`].
+const NUMBERS: [&str; FIGURES.len()] = {
+    let mut numbers = [""; FIGURES.len()];
+    let mut idx = 0;
+    while idx < FIGURES.len() {
+        numbers[idx] = FIGURES[idx].number;
+        idx += 1;
+    }
+    numbers
+};
+
+/// Edge on the same foot and in the same direction, curving the other way.
+fn reverse_edge(code: Code) -> Code {
+    Code {
+        edge: code.edge.opposite(),
+        ..code
+    }
+}
+
+/// Builder that accumulates the moves for a figure.
+struct Builder {
+    text_pos: TextPosition,
+    /// Radius of each circle, in centimetres.
+    radius: f64,
+    /// Whether edges are labelled.
+    labels: bool,
+    /// Edge at the end of the moves so far.
+    code: Option<Code>,
+    moves: Vec<Box<dyn Move>>,
+}
+
+impl Builder {
+    fn curve(&mut self, code: Code, degrees: f64, len: f64, label: &str) -> Result<(), ParseError> {
+        let angle = degrees.round() as i32;
+        if angle <= 0 {
+            return Ok(());
+        }
+        let label = if self.labels { label } else { " " };
+        let len = len.round() as i32;
+        let mv = format!("{code}[angle={angle},len={len},label=\"{label}\"]");
+        self.moves.push(Curve::construct(&mv, self.text_pos)?);
+        Ok(())
+    }
+
+    fn shift(&mut self, fwd: i64, side: i64, rotate: i32, code: Code) -> Result<(), ParseError> {
+        let mv = format!("Shift[fwd={fwd},side={side},rotate={rotate},code=\"{code}\"]");
+        self.moves.push(Shift::construct(&mv, self.text_pos)?);
+        Ok(())
+    }
+
+    fn label(&mut self, text: &str, side: i32) -> Result<(), ParseError> {
+        if text.is_empty() || !self.labels {
+            return Ok(());
+        }
+        let mv = format!("{}[text=\"{text}\",side={side}]", Label::MOVE);
+        self.moves.push(Label::construct(&mv, self.text_pos)?);
+        Ok(())
+    }
+
+    /// Skate part of the circle, splitting it so that no single edge covers more than a half circle.
+    fn arc(&mut self, code: Code, degrees: f64, label: &str) -> Result<(), ParseError> {
+        let parts = if degrees > 180.0 { 2 } else { 1 };
+        let degrees = degrees / parts as f64;
+        let len = self.radius * degrees.to_radians();
+        for part in 0..parts {
+            self.curve(code, degrees, len, if part == 0 { label } else { " " })?;
+        }
+        Ok(())
+    }
+
+    /// Perform a turn at the current point on the circle, returning the edge afterwards.
+    fn turn(&mut self, turn: Turn, code: Code) -> Result<Code, ParseError> {
+        let out_code = turn.exit(code);
+        if turn == Turn::Loop {
+            // A loop is a small circle inside the main circle, on the same edge.
+            let radius = self.radius * LOOP_SIZE;
+            self.curve(code, 180.0, radius * PI, " ")?;
+            self.curve(code, 180.0, radius * PI, " ")?;
+            return Ok(out_code);
+        }
+
+        // The turn is symmetric about a radius of the circle, and replaces the part of the circle that is within
+        // `TURN_SPREAD` of that radius.  Each half of the turn is an arc that starts tangent to the circle and finishes
+        // at the point of the turn, which is inside the circle for a three turn and outside it for a bracket.
+        let spread = TURN_SPREAD.to_radians();
+        let depth = match turn {
+            Turn::Three => self.radius * TURN_DEPTH,
+            _ => -self.radius * TURN_DEPTH,
+        };
+        let fwd = (self.radius - depth) * spread.sin();
+        let inward = self.radius - (self.radius - depth) * spread.cos();
+        let hook_radius = (fwd * fwd + inward * inward) / (2.0 * inward.abs());
+        // Work in whole degrees throughout, so that rounding errors do not accumulate around the circle.
+        let hook_angle = fwd.atan2(hook_radius - inward.abs()).to_degrees().round();
+        let hook_len = hook_radius * hook_angle.to_radians();
+        let (in_code, hook_out_code, signed_angle) = if inward > 0.0 {
+            (code, out_code, hook_angle)
+        } else {
+            (reverse_edge(code), reverse_edge(out_code), -hook_angle)
+        };
+
+        // Positive rotations and sideways offsets are clockwise and to the right.
+        let sign = match code.curve_direction() {
+            Some(RotationDirection::Clockwise) => 1,
+            _ => -1,
+        };
+        let rotate = (2.0 * (TURN_SPREAD - signed_angle)).round() as i32 * sign;
+        let label_side = match turn {
+            Turn::Three => -sign * TURN_LABEL_OFFSET,
+            _ => sign * TURN_LABEL_OFFSET,
+        };
+
+        self.curve(in_code, hook_angle, hook_len, " ")?;
+        self.label(turn.label(), label_side)?;
+        self.shift(0, 0, rotate, hook_out_code)?;
+        self.curve(hook_out_code, hook_angle, hook_len, " ")?;
+        Ok(out_code)
+    }
+
+    /// Skate a circle of the figure, starting at the centre of the figure.
+    fn lobe(&mut self, lobe: &Lobe) -> Result<(), ParseError> {
+        if let Some(prev) = self.code {
+            if prev.foot != lobe.code.foot {
+                // Push onto the new foot at the centre of the figure, away from the centre of the previous circle and
+                // carrying on in the same direction of travel, so that the next circle is tangent to the previous one.
+                let side = match prev.curve_direction() {
+                    Some(RotationDirection::Clockwise) => 2 * moves::HW.0,
+                    _ => -2 * moves::HW.0,
+                };
+                self.shift(0, side, 0, lobe.code)?;
+            }
+        }
+
+        let mut code = lobe.code;
+        let mut label = code.to_string();
+        let mut from = 0.0;
+        if let Some((turn, count)) = lobe.turns {
+            let spread = if turn == Turn::Loop { 0.0 } else { TURN_SPREAD };
+            for idx in 1..=count {
+                let at = 360.0 * idx as f64 / (count + 1) as f64;
+                self.arc(code, at - spread - from, &label)?;
+                code = self.turn(turn, code)?;
+                label = if turn == Turn::Loop {
+                    " ".to_string()
+                } else {
+                    code.to_string()
+                };
+                from = at + spread;
+            }
+        }
+        let to = if lobe.half { 180.0 } else { 360.0 };
+        self.arc(code, to - from, &label)?;
+        self.code = Some(code);
+        Ok(())
+    }
+}
+
+/// Marker for the starting point of a figure, for use within a [`Figure`].
+#[derive(Debug, Clone)]
+struct Start {
+    text_pos: TextPosition,
+    pre_transition: PreTransition,
+    code: Code,
+}
+
+impl Move for Start {
+    fn id(&self) -> MoveId {
+        MoveId::Skating(SkatingMoveId::Figure)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        Vec::new()
+    }
+    fn start(&self) -> Option<Code> {
+        Some(self.code)
+    }
+    fn end(&self) -> Option<Code> {
+        Some(self.code)
+    }
+    fn text(&self) -> String {
+        format!("{}{}-Start", self.code, Figure::MOVE)
+    }
+    fn expanded_text(&self) -> String {
+        self.text()
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn pre_transition(&self, from: Code) -> Transition {
+        self.pre_transition.perform(from, self.code)
+    }
+    fn pre_transition_warning(&self, from: Code, strict: bool) -> Option<WarningKind> {
+        self.pre_transition.warning(from, self.code, strict)
+    }
+    fn transition(&self) -> Transition {
+        Transition {
+            spatial: SpatialTransition::Relative {
+                delta: Position::default(),
+                rotate: Rotation::default(),
+            },
+            code: Some(self.code),
+        }
+    }
+    fn defs(&self, _opts: &mut RenderOptions) -> Vec<(SvgId, Group)> {
+        let grp = Group::new().add(info::marker().set("style", "stroke:green;"));
+        vec![(SvgId(self.text()), grp)]
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        Box::new(Self {
+            text_pos: self.text_pos.at_repeat(repeat),
+            code: self.code.opposite(),
+            ..self.clone()
+        })
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+}
+
+/// Compulsory (school) figure.
+pub struct Figure;
+
+impl Figure {
+    /// Move code suffix.
+    pub const MOVE: &'static str = "-Fig";
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: "Figure",
+        id: MoveId::Skating(SkatingMoveId::Figure),
+        summary: "Compulsory school figure by ISU number, starting on either foot",
+        example: "RFI-Fig[number=\"2\"]",
+        visible: true,
+        params: &[
+            params::Info {
+                name: "number",
+                doc: "Number of the figure: 1-4 (circle eight), 5a-6b (serpentine), 7-8b (three), 10a-b (double three), 14-17 (loop), 18a-19b (bracket), 31a-b (paragraph double three), 33a-b (paragraph loop), 39a-b (paragraph bracket)",
+                default: Value::Text(Cow::Borrowed("1")),
+                range: params::Range::Choice(&NUMBERS),
+                short: None,
+            },
+            params::Info {
+                name: "diameter",
+                doc: "Diameter of each circle in centimetres; 0 for the standard size for the figure",
+                default: Value::Number(0),
+                range: params::Range::Positive,
+                short: None,
+            },
+            params::Info {
+                name: "tracings",
+                doc: "Number of times the figure is traced",
+                default: Value::Number(1),
+                range: params::Range::StrictlyPositive,
+                short: None,
+            },
+        ],
+    };
+
+    pub fn from_params(
+        input: &str,
+        text_pos: TextPosition,
+        pre_transition: PreTransition,
+        entry_code: Code,
+        params: Vec<MoveParam>,
+        ctx: &mut moves::Context,
+    ) -> Result<Compound, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        ctx.prev_label = None;
+
+        let number = params[0].value.as_str(text_pos)?;
+        let diameter = params[1].value.as_cm(text_pos)?;
+        let tracings = params[2].value.as_i32(text_pos)?;
+
+        let Some(schedule) = FIGURES.iter().find(|figure| figure.number == number) else {
+            return Err(ParseError {
+                pos: text_pos,
+                msg: format!("Unknown figure number \"{number}\""),
+                file: None,
+                calls: Vec::new(),
+            });
+        };
+        // The figure can be started on either foot, in which case every circle is skated on the other foot.
+        let first = schedule.lobes[0].code;
+        let mirror = if entry_code == first {
+            false
+        } else if entry_code == first.opposite() {
+            true
+        } else {
+            return Err(edge_err(text_pos, entry_code, Self::INFO));
+        };
+
+        let size = if diameter == Centimetres(0) {
+            schedule.diameter() as f64
+        } else {
+            diameter.0 as f64
+        };
+        let mut builder = Builder {
+            text_pos,
+            radius: size / 2.0,
+            labels: true,
+            code: None,
+            moves: vec![Box::new(Start {
+                text_pos,
+                pre_transition,
+                code: entry_code,
+            })],
+        };
+        for tracing in 0..tracings {
+            // Only label the first tracing, as later tracings follow the same path.
+            builder.labels = tracing == 0;
+            for lobe in schedule.lobes {
+                if mirror {
+                    builder.lobe(&Lobe {
+                        code: lobe.code.opposite(),
+                        ..*lobe
+                    })?;
+                } else {
+                    builder.lobe(lobe)?;
+                }
+            }
+        }
+        log::info!(
+            "input {input:?} for figure {number} ({}) expands to {} moves",
+            schedule.name,
+            builder.moves.len()
+        );
+
+        let text_prefix = format!("{}{entry_code}{}", pre_transition.prefix(), Self::MOVE);
+
+        // Timing information goes on the first edge, after the start marker.
+        Ok(Compound::new_with_count_idx(
+            text_pos,
+            SkatingMoveId::Figure,
+            builder.moves,
+            Self::INFO.params,
+            params,
+            text_prefix,
+            Some(1),
+        ))
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2025 David Drysdale
+
+//! Pseudo-move definition for synchronized skating formations.
+
+use crate::{
+    moves::{self, MoveId, PseudoMoveId},
+    param, params,
+    params::Value,
+    pos, Bounds, Centimetres, Direction, Document, MainFontSize, Move, MoveParam, ParseError,
+    Position, RenderOptions, Rotation, Skater, SvgId, TextPosition,
+};
+
+#[derive(Debug, Clone)]
+pub struct Formation {
+    text_pos: TextPosition,
+    count: u32,
+    rows: u32,
+    spacing: Centimetres,
+    radius: Centimetres,
+    wheel: bool,
+    labels: bool,
+}
+
+impl Formation {
+    pub const MOVE: &'static str = "Formation";
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::Formation),
+        summary: "Render subsequent moves for every skater in a synchronized skating formation",
+        example: "Formation[count=6,radius=200,wheel=true]",
+        visible: false,
+        params: &[
+            params::Info {
+                name: "count",
+                doc: "Number of skaters in the formation, including the current skater; 1 to end the formation",
+                default: Value::Number(4),
+                range: params::Range::StrictlyPositive,
+                short: None,
+            },
+            params::Info {
+                name: "rows",
+                doc: "Number of rows for a block formation; 1 for a line",
+                default: Value::Number(1),
+                range: params::Range::StrictlyPositive,
+                short: None,
+            },
+            params::Info {
+                name: "spacing",
+                doc: "Distance between skaters in a line or block, in centimetres",
+                default: Value::Number(150),
+                range: params::Range::StrictlyPositive,
+                short: None,
+            },
+            params::Info {
+                name: "radius",
+                doc: "Radius of a circle or wheel formation, in centimetres; 0 for a line or block",
+                default: Value::Number(0),
+                range: params::Range::Positive,
+                short: None,
+            },
+            params::Info {
+                name: "wheel",
+                doc: "Whether the formation is a wheel that rotates about its centre, rather than moving rigidly",
+                default: Value::Boolean(false),
+                range: params::Range::Boolean,
+                short: None,
+            },
+            params::Info {
+                name: "labels",
+                doc: "Whether to show labels for every skater in the formation",
+                default: Value::Boolean(false),
+                range: params::Range::Boolean,
+                short: None,
+            },
+        ],
+    };
+
+    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        let formation = Self {
+            text_pos,
+            count: params[0].value.as_i32(text_pos)? as u32,
+            rows: params[1].value.as_i32(text_pos)? as u32,
+            spacing: params[2].value.as_cm(text_pos)?,
+            radius: params[3].value.as_cm(text_pos)?,
+            wheel: params[4].value.as_bool(text_pos)?,
+            labels: params[5].value.as_bool(text_pos)?,
+        };
+        if formation.wheel && formation.radius == Centimetres(0) {
+            return Err(ParseError {
+                pos: text_pos,
+                msg: "Wheel formation needs a non-zero radius".to_string(),
+                file: None,
+                calls: Vec::new(),
+            });
+        }
+        Ok(formation)
+    }
+
+    /// Anchor the formation relative to the current position of the reference skater, returning `None` if there
+    /// are no other skaters in the formation.
+    pub fn anchor(&self, skater: &Skater) -> Option<Anchored<'_>> {
+        if self.count <= 1 {
+            return None;
+        }
+        Some(Anchored {
+            formation: self,
+            centre: skater.pos.add_rotated(skater.dir, self.centre_offset()),
+        })
+    }
+
+    /// Offset of the centre of a circle or wheel, relative to the reference skater.
+    fn centre_offset(&self) -> Position {
+        Position {
+            x: self.radius,
+            y: Centimetres(0),
+        }
+    }
+
+    /// Rotation of the given slot around a circle or wheel.
+    fn slot_rotation(&self, slot: u32) -> Rotation {
+        Rotation((360 * slot / self.count) as i32)
+    }
+
+    /// Offset of the given slot relative to the reference skater, for a formation that moves rigidly.
+    fn slot_offset(&self, slot: u32) -> Position {
+        if self.radius > Centimetres(0) {
+            let rotate = Direction::new(self.slot_rotation(slot).0);
+            self.centre_offset()
+                .add_rotated(rotate, pos!(-self.radius.0, 0))
+        } else {
+            // Skaters in each row are abreast of each other, to the left of the reference skater, with subsequent
+            // rows behind.
+            let cols = self.count.div_ceil(self.rows);
+            let (row, col) = (slot / cols, slot % cols);
+            pos!(self.spacing.0 * col as i64, -self.spacing.0 * row as i64)
+        }
+    }
+}
+
+/// A [`Formation`] that has been anchored at a particular location.
+#[derive(Debug, Clone)]
+pub struct Anchored<'a> {
+    formation: &'a Formation,
+    /// Fixed centre of a wheel.
+    centre: Position,
+}
+
+impl Anchored<'_> {
+    /// Return the positions of the other skaters in the formation, given the position of the reference skater.
+    pub fn skaters_for(&self, skater: &Skater) -> Vec<Skater> {
+        (1..self.formation.count)
+            .map(|slot| {
+                if self.formation.wheel {
+                    let rotate = self.formation.slot_rotation(slot);
+                    Skater {
+                        pos: self
+                            .centre
+                            .add_rotated(Direction::new(rotate.0), skater.pos - self.centre),
+                        dir: skater.dir + rotate,
+                        code: skater.code,
+                    }
+                } else {
+                    Skater {
+                        pos: skater
+                            .pos
+                            .add_rotated(skater.dir, self.formation.slot_offset(slot)),
+                        ..*skater
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Return the bounds of a move performed by all the other skaters in the formation.
+    pub fn bounds_for(&self, mv: &dyn Move, skater: &Skater) -> Option<Bounds> {
+        let mut result: Option<Bounds> = None;
+        for other in self.skaters_for(skater) {
+            if let Some(bounds) = mv.bounds(&other) {
+                match &mut result {
+                    Some(result) => result.encompass_bounds(&bounds),
+                    None => result = Some(bounds),
+                }
+            }
+        }
+        result
+    }
+
+    /// Render a move performed by all the other skaters in the formation.
+    pub fn render_for(
+        &self,
+        mut doc: Document,
+        mv: &dyn Move,
+        skater: &Skater,
+        opts: &mut RenderOptions,
+    ) -> Document {
+        let saved = (opts.count, opts.duration, opts.font_size);
+        if !self.formation.labels {
+            opts.font_size = MainFontSize::None;
+            opts.count = None;
+            opts.duration = None;
+        }
+        for other in self.skaters_for(skater) {
+            doc = mv.render(doc, &other, opts, None);
+        }
+        (opts.count, opts.duration, opts.font_size) = saved;
+        doc
+    }
+}
+
+impl Move for Formation {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::Formation)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![
+            param!("count" = (self.count as i32)),
+            param!("rows" = (self.rows as i32)),
+            param!("spacing" = (self.spacing.0 as i32)),
+            param!("radius" = (self.radius.0 as i32)),
+            param!(self.wheel),
+            param!(self.labels),
+        ]
+    }
+    fn text(&self) -> String {
+        let params = params::to_string(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn expanded_text(&self) -> String {
+        let params = params::to_expanded(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn bounds(&self, _before: &Skater) -> Option<Bounds> {
+        None
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &Skater,
+        _opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        doc
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        self.box_clone(repeat)
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+    fn as_formation(&self) -> Option<&Formation> {
+        Some(self)
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     params::{self, Value},
     parser, Centimetres, Code, Edge, Foot, Label, Move, MoveParam, ParseError, Percentage,
     Position, PreTransition, RenderOptions, Rotation, SkatingDirection, SpatialTransition, SvgId,
-    TextPosition, Transition,
+    TextPosition, Transition, WarningKind,
 };
 use nom::bytes::complete::tag;
 use std::borrow::Cow;
@@ -69,11 +69,15 @@ impl Hop {
             return Err(ParseError {
                 pos: text_pos,
                 msg: format!("Entry edge {entry_code} not supported"),
+                file: None,
+                calls: Vec::new(),
             });
         }
         let (rest, _move) = tag(Self::MOVE)(rest).map_err(|_e: parser::Error| ParseError {
             pos: text_pos,
             msg: format!("Missing expected '{}'", Self::MOVE),
+            file: None,
+            calls: Vec::new(),
         })?;
 
         let params = params::populate(Self::INFO.params, rest, text_pos)?;
@@ -150,6 +154,9 @@ impl Move for Hop {
     fn pre_transition(&self, from: Code) -> Transition {
         self.pre_transition.perform(from, self.code())
     }
+    fn pre_transition_warning(&self, from: Code, strict: bool) -> Option<WarningKind> {
+        self.pre_transition.warning(from, self.code(), strict)
+    }
     fn transition(&self) -> Transition {
         Transition {
             spatial: SpatialTransition::Relative {
@@ -168,10 +175,10 @@ impl Move for Hop {
         vec![(SvgId(self.text()), grp)]
     }
     fn labels(&self, opts: &RenderOptions) -> Vec<Label> {
-        let text = match &self.label {
+        let text = opts.label_text(match &self.label {
             Some(label) => label.clone(),
             None => "Hop".to_string(),
-        };
+        });
         let label_offset_fraction = self.label_offset.for_opts(opts);
         let dist = cm!((30.0 * label_offset_fraction) as i64);
         vec![Label {
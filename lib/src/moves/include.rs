@@ -0,0 +1,103 @@
+// Copyright 2025 David Drysdale
+
+//! Pseudo-move definition for including moves from another file.
+
+use crate::{
+    moves::{self, MoveId, PseudoMoveId},
+    param, params,
+    params::Value,
+    Bounds, Document, Move, MoveParam, ParseError, RenderOptions, Skater, SvgId, TextPosition,
+};
+use std::{borrow::Cow, collections::HashMap};
+
+#[derive(Debug, Clone)]
+pub struct Include {
+    text_pos: TextPosition,
+    pub file: String,
+    /// Values of the variables defined before the `Include`, which are also available in the included file.
+    pub vars: HashMap<String, i32>,
+}
+
+impl Include {
+    pub const MOVE: &'static str = "Include";
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::Include),
+        summary: "Include the moves from another file",
+        example: "Include[file=\"waltz.skate\"]",
+        visible: false,
+        params: &[params::Info {
+            name: "file",
+            doc: "Name of the file to include",
+            default: Value::Text(Cow::Borrowed("")),
+            range: params::Range::Text,
+            short: None,
+        }],
+    };
+
+    pub fn from_params(
+        text_pos: TextPosition,
+        params: Vec<MoveParam>,
+        vars: &HashMap<String, i32>,
+    ) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        let file = params[0].value.as_str(text_pos)?;
+        if file.is_empty() {
+            return Err(ParseError {
+                pos: text_pos,
+                msg: format!("{} needs a non-empty file name", Self::MOVE),
+                file: None,
+                calls: Vec::new(),
+            });
+        }
+        Ok(Self {
+            text_pos,
+            file: file.to_string(),
+            vars: vars.clone(),
+        })
+    }
+}
+
+impl Move for Include {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::Include)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![param!(self.file)]
+    }
+    fn text(&self) -> String {
+        let params = params::to_string(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn expanded_text(&self) -> String {
+        let params = params::to_expanded(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn bounds(&self, _before: &Skater) -> Option<Bounds> {
+        None
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &Skater,
+        _opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        doc
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        self.box_clone(repeat)
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+    fn as_include(&self) -> Option<&Include> {
+        Some(self)
+    }
+}
@@ -9,7 +9,12 @@ use crate::{
     path, Bounds, Centimetres, Count, Document, FontSize, MainFontSize, Move, MoveParam,
     ParseError, Percentage, Position, RenderOptions, Skater, StrokeWidth, SvgId, TextPosition,
 };
-use svg::node::element::Group;
+use svg::node::element::{Group, Path};
+
+/// Marker for the start or end of a move, pointing in the direction of travel.
+pub(crate) fn marker() -> Path {
+    path!("M 0,0 l 10,0 l -20,0 l 10,0 l 0,20 l 8,-8 l -8,8 l-8,-8 l 8,8 l 0,-30 l 0,10")
+}
 
 #[derive(Debug, Clone)]
 pub struct Info {
@@ -23,6 +28,7 @@ pub struct Info {
     stroke_width: Option<StrokeWidth>,
     label_offset: Percentage,
     auto_count: bool,
+    strict: bool,
 }
 
 impl Info {
@@ -105,6 +111,13 @@ impl Info {
                 range: params::Range::Boolean,
                 short: None,
             },
+            params::Info {
+                name: "strict",
+                doc: "Whether to warn about impossible changes of foot, direction or edge between moves",
+                default: Value::Boolean(false),
+                range: params::Range::Boolean,
+                short: None,
+            },
         ],
     };
 
@@ -135,6 +148,7 @@ impl Info {
             },
             label_offset: params[8].value.as_percent(text_pos)?,
             auto_count: params[9].value.as_bool(text_pos)?,
+            strict: params[10].value.as_bool(text_pos)?,
         })
     }
 }
@@ -155,6 +169,7 @@ impl Move for Info {
             param!("stroke-width" = (self.stroke_width.map(|v| v.0).unwrap_or(0))),
             param!("label-offset" = self.label_offset.0),
             param!("auto-count" = self.auto_count),
+            param!(self.strict),
         ]
     }
     fn text(&self) -> String {
@@ -176,22 +191,17 @@ impl Move for Info {
         opts.show_bounds = self.bounds;
         opts.grid = self.grid.map(|g| g.0 as usize);
         opts.show_move_bounds = self.move_bounds;
+        if self.strict {
+            opts.strict = true;
+        }
 
         let mut grp = Group::new();
         if self.markers {
+            grp = grp.add(marker().set("style", "stroke:red;").set("id", "end-mark"));
             grp = grp.add(
-                path!(
-                    "M 0,0 l 10,0 l -20,0 l 10,0 l 0,20 l 8,-8 l -8,8 l-8,-8 l 8,8 l 0,-30 l 0,10",
-                )
-                .set("style", "stroke:red;")
-                .set("id", "end-mark"),
-            );
-            grp = grp.add(
-                path!(
-                    "M 0,0 l 10,0 l -20,0 l 10,0 l 0,20 l 8,-8 l -8,8 l-8,-8 l 8,8 l 0,-30 l 0,10",
-                )
-                .set("style", "stroke:green;")
-                .set("id", "start-mark"),
+                marker()
+                    .set("style", "stroke:green;")
+                    .set("id", "start-mark"),
             );
         }
         vec![(SvgId(self.text()), grp)]
@@ -11,8 +11,8 @@ use super::{
     MoveId, SkatingMoveId,
 };
 use crate::{
-    code, moves, params, params::Value, Code, JumpCount, MoveParam, ParseError, PreTransition,
-    TextPosition,
+    code, moves, params, params::Value, Code, JumpCombo, JumpCount, JumpKind, Move, MoveParam,
+    ParseError, PreTransition, TextPosition,
 };
 use std::borrow::Cow;
 
@@ -680,3 +680,477 @@ impl Lutz {
         ))
     }
 }
+
+/// Return the take-off edge for a kind of jump, for an anti-clockwise (regular) jump or its clockwise equivalent.
+fn take_off_code(kind: JumpKind, regular: bool) -> Code {
+    let code = match kind {
+        JumpKind::Salchow | JumpKind::Flip => code!(LBI),
+        JumpKind::ToeLoop | JumpKind::Loop | JumpKind::Euler => code!(RBO),
+        JumpKind::Lutz => code!(LBO),
+        JumpKind::Axel => code!(LFO),
+    };
+    if regular {
+        code
+    } else {
+        code.opposite()
+    }
+}
+
+/// Build the compound move for a single jump.
+#[allow(clippy::too_many_arguments)]
+fn single_jump(
+    input: &str,
+    text_pos: TextPosition,
+    pre_transition: PreTransition,
+    entry_code: Code,
+    kind: JumpKind,
+    count: JumpCount,
+    params: Vec<MoveParam>,
+    ctx: &mut moves::Context,
+) -> Result<Compound, ParseError> {
+    match kind {
+        JumpKind::Salchow => Salchow::from_params(
+            input,
+            text_pos,
+            pre_transition,
+            entry_code,
+            count,
+            params,
+            ctx,
+        ),
+        JumpKind::ToeLoop => ToeLoop::from_params(
+            input,
+            text_pos,
+            pre_transition,
+            entry_code,
+            count,
+            params,
+            ctx,
+        ),
+        JumpKind::Loop => Loop::from_params(
+            input,
+            text_pos,
+            pre_transition,
+            entry_code,
+            count,
+            params,
+            ctx,
+        ),
+        JumpKind::Flip => Flip::from_params(
+            input,
+            text_pos,
+            pre_transition,
+            entry_code,
+            count,
+            params,
+            ctx,
+        ),
+        JumpKind::Lutz => Lutz::from_params(
+            input,
+            text_pos,
+            pre_transition,
+            entry_code,
+            count,
+            params,
+            ctx,
+        ),
+        JumpKind::Axel => Axel::from_params(
+            input,
+            text_pos,
+            pre_transition,
+            entry_code,
+            count,
+            params,
+            ctx,
+        ),
+        JumpKind::Euler => Euler::from_params(
+            input,
+            text_pos,
+            pre_transition,
+            entry_code,
+            count,
+            params,
+            ctx,
+        ),
+    }
+}
+
+/// Jump combination or sequence.
+pub struct Combo;
+
+impl Combo {
+    /// Suffix that marks a jump sequence rather than a combination.
+    pub const SEQUENCE: &'static str = "SEQ";
+    /// Length of the landing edge that acts as the take-off for the next jump in a combination, in centimetres.
+    const LINK_LEN: i32 = 200;
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: "Jump Combination",
+        id: MoveId::Skating(SkatingMoveId::JumpCombo(JumpCombo::pair(
+            (JumpKind::Lutz, JumpCount::Single),
+            (JumpKind::ToeLoop, JumpCount::Single),
+        ))),
+        summary: "Combination of jumps, or a jump sequence with a +SEQ suffix",
+        example: "LBO-1Lz+1T",
+        visible: true,
+        params: &JUMP_PARAMS,
+    };
+
+    pub fn from_params(
+        input: &str,
+        text_pos: TextPosition,
+        pre_transition: PreTransition,
+        entry_code: Code,
+        combo: JumpCombo,
+        params: Vec<MoveParam>,
+        ctx: &mut moves::Context,
+    ) -> Result<Compound, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        let jump_label = params[5].value.as_str(text_pos)?;
+        let label = if jump_label.is_empty() {
+            combo.to_string()
+        } else {
+            jump_label.to_string()
+        };
+
+        let jumps = combo.jumps();
+        let regular = entry_code == take_off_code(jumps[0].0, true);
+        let mut moves = Vec::new();
+        let mut code = entry_code;
+        for (idx, (kind, count)) in jumps.iter().enumerate() {
+            // The first jump carries the label for the whole combination.
+            let mut jump_params = params.clone();
+            jump_params[5].value = Value::Text(Cow::Owned(if idx == 0 {
+                label.clone()
+            } else {
+                " ".to_string()
+            }));
+            let mut jump_pre_transition = pre_transition;
+            if idx > 0 {
+                // Later jumps use the default entry, but in a combination the entry is a short continuation of the
+                // previous landing edge.
+                jump_params[0].value = JUMP_PARAMS[0].default.clone();
+                jump_params[1].value = if combo.sequence {
+                    JUMP_PARAMS[1].default.clone()
+                } else {
+                    Value::Number(Self::LINK_LEN)
+                };
+                jump_pre_transition = PreTransition::Normal;
+                let take_off = take_off_code(*kind, regular);
+                if !combo.sequence && take_off != code {
+                    return Err(ParseError {
+                        pos: text_pos,
+                        msg: format!(
+                            "{count}{kind} takes off from {take_off} so cannot follow a {code} landing in a combination"
+                        ),
+                        file: None,
+                        calls: Vec::new(),
+                    });
+                }
+                code = take_off;
+            }
+            let jump = single_jump(
+                input,
+                text_pos,
+                jump_pre_transition,
+                code,
+                *kind,
+                *count,
+                jump_params,
+                ctx,
+            )?;
+            code = jump.end().expect("jump has a landing edge");
+            let mut jump_moves = jump.into_moves();
+            if idx < jumps.len() - 1 && !combo.sequence {
+                // Drop the exit edge, as the landing edge continues into the next jump.
+                jump_moves.pop();
+            }
+            moves.extend(jump_moves);
+        }
+
+        let prefix = pre_transition.prefix();
+        let text_prefix = format!("{prefix}{entry_code}-{combo}");
+
+        Ok(Compound::new(
+            text_pos,
+            SkatingMoveId::JumpCombo(combo),
+            moves,
+            Self::INFO.params,
+            params,
+            text_prefix,
+        ))
+    }
+}
+
+/// Shape of a minor jump, given for the regular (anti-clockwise) variant.
+#[derive(Clone, Copy)]
+struct Shape {
+    /// Take-off edge.
+    take_off: Code,
+    /// Whether the take-off is assisted by a toe pick.
+    toe: bool,
+    /// Foot and direction for the landing.
+    hop: Code,
+    /// Edge skated after landing.
+    exit: Code,
+}
+
+/// Build a minor jump: an entry edge, an optional toe pick, a hop and an exit edge.
+#[allow(clippy::too_many_arguments)]
+fn minor_jump(
+    input: &str,
+    text_pos: TextPosition,
+    pre_transition: PreTransition,
+    entry_code: Code,
+    params: Vec<MoveParam>,
+    ctx: &mut moves::Context,
+    id: SkatingMoveId,
+    info: moves::Info,
+    shape: Shape,
+    default_label: String,
+    suffix: &str,
+) -> Result<Compound, ParseError> {
+    let regular = if entry_code == shape.take_off {
+        true
+    } else if entry_code == shape.take_off.opposite() {
+        false
+    } else {
+        return Err(edge_err(text_pos, entry_code, info));
+    };
+    ctx.prev_label = None;
+
+    let entry_angle = params[0].value.as_rotation(text_pos)?;
+    let entry_len = params[1].value.as_cm(text_pos)?;
+    let exit_angle = params[2].value.as_rotation(text_pos)?;
+    let exit_len = params[3].value.as_cm(text_pos)?;
+    let style = params[4].value.as_str(text_pos)?;
+    let jump_label = params[5].value.as_str(text_pos)?;
+    let label_offset = params[6].value.as_percent(text_pos)?;
+
+    let prefix = pre_transition.prefix();
+    let (hop_code, out_code, side) = if regular {
+        (shape.hop, shape.exit, 1)
+    } else {
+        (shape.hop.opposite(), shape.exit.opposite(), -1)
+    };
+
+    let entry = format!("{prefix}{entry_code}[angle={entry_angle},len={entry_len},style=\"{style}\",label-offset={label_offset}]");
+    let shift1 = format!("Shift[side={},fwd=50]", 100 * side);
+    let label = if jump_label.is_empty() {
+        default_label
+    } else {
+        jump_label.to_string()
+    };
+    let hop = format!("{hop_code}-Hop [label=\"{label}\"]");
+    let shift2 = format!("Shift[side={},fwd=150]", -50 * side);
+    let exit = format!(
+        "{out_code}[angle={exit_angle},len={exit_len},style=\"{style}\",label-offset={label_offset}]"
+    );
+
+    let mut moves = vec![Curve::construct(&entry, text_pos)];
+    if shape.toe {
+        log::info!("input {input:?} results in {entry};{shift1};{hop};{shift2};{exit}");
+        moves.push(Shift::construct(&shift1, text_pos));
+    } else {
+        log::info!("input {input:?} results in {entry};{hop};{shift2};{exit}");
+    }
+    moves.push(Hop::construct(&hop, text_pos));
+    moves.push(Shift::construct(&shift2, text_pos));
+    moves.push(Curve::construct(&exit, text_pos));
+
+    let text_prefix = format!("{prefix}{entry_code}{suffix}");
+
+    Ok(Compound::new(
+        text_pos,
+        id,
+        map_errs(moves)?,
+        info.params,
+        params,
+        text_prefix,
+    ))
+}
+
+/// Euler (half loop) jump.
+pub struct Euler;
+
+impl Euler {
+    /// Jump code.
+    pub const JUMP: &'static str = "Eu";
+    const SHAPE: Shape = Shape {
+        take_off: code!(RBO),
+        toe: false,
+        hop: code!(LB),
+        exit: code!(LBI),
+    };
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: "Euler",
+        id: MoveId::Skating(SkatingMoveId::Euler(JumpCount::Single)),
+        summary: "Euler (half loop) jump, which always has a single rotation",
+        example: "RBO-1Eu",
+        visible: true,
+        params: &JUMP_PARAMS,
+    };
+
+    pub fn from_params(
+        input: &str,
+        text_pos: TextPosition,
+        pre_transition: PreTransition,
+        entry_code: Code,
+        count: JumpCount,
+        params: Vec<MoveParam>,
+        ctx: &mut moves::Context,
+    ) -> Result<Compound, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        if count != JumpCount::Single {
+            return Err(ParseError {
+                pos: text_pos,
+                msg: format!("{} only has a single rotation", Self::INFO.name),
+                file: None,
+                calls: Vec::new(),
+            });
+        }
+        let code = format!("{count}{}", Self::JUMP);
+        minor_jump(
+            input,
+            text_pos,
+            pre_transition,
+            entry_code,
+            params,
+            ctx,
+            SkatingMoveId::Euler(count),
+            Self::INFO,
+            Self::SHAPE,
+            code.clone(),
+            &format!("-{code}"),
+        )
+    }
+}
+
+/// Define a minor jump that has no rotation count.
+macro_rules! minor_jump {
+    {
+        $(#[$meta:meta])*
+        $name:ident, $code:literal, $info_name:literal, $summary:literal, $example:literal, $label:literal,
+        $shape:expr
+    } => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl $name {
+            /// Move code.
+            pub const MOVE: &'static str = $code;
+            const SHAPE: Shape = $shape;
+            /// Static move information.
+            pub const INFO: moves::Info = moves::Info {
+                name: $info_name,
+                id: MoveId::Skating(SkatingMoveId::$name),
+                summary: $summary,
+                example: $example,
+                visible: true,
+                params: &JUMP_PARAMS,
+            };
+
+            pub fn from_params(
+                input: &str,
+                text_pos: TextPosition,
+                pre_transition: PreTransition,
+                entry_code: Code,
+                params: Vec<MoveParam>,
+                ctx: &mut moves::Context,
+            ) -> Result<Compound, ParseError> {
+                assert!(params::compatible(Self::INFO.params, &params));
+                minor_jump(
+                    input,
+                    text_pos,
+                    pre_transition,
+                    entry_code,
+                    params,
+                    ctx,
+                    SkatingMoveId::$name,
+                    Self::INFO,
+                    Self::SHAPE,
+                    $label.to_string(),
+                    Self::MOVE,
+                )
+            }
+        }
+    };
+}
+
+minor_jump! {
+    /// Waltz jump.
+    WaltzJump, "-Wz", "Waltz Jump", "Waltz jump, with a half rotation from a forward outside edge", "LFO-Wz",
+    "Waltz",
+    Shape { take_off: code!(LFO), toe: false, hop: code!(RB), exit: code!(RBO) }
+}
+
+minor_jump! {
+    /// Half flip jump.
+    HalfFlip, "-HalfF", "Half Flip", "Half flip jump, landing forwards", "LBI-HalfF", "Half F",
+    Shape { take_off: code!(LBI), toe: true, hop: code!(RF), exit: code!(LFO) }
+}
+
+minor_jump! {
+    /// Half Lutz jump.
+    HalfLutz, "-HalfLz", "Half Lutz", "Half Lutz jump, landing forwards", "LBO-HalfLz", "Half Lz",
+    Shape { take_off: code!(LBO), toe: true, hop: code!(RF), exit: code!(LFO) }
+}
+
+minor_jump! {
+    /// Split jump.
+    SplitJump, "-Split", "Split Jump", "Split jump, with a half rotation and the legs in a split", "LBI-Split",
+    "Split",
+    Shape { take_off: code!(LBI), toe: true, hop: code!(RF), exit: code!(LFO) }
+}
+
+minor_jump! {
+    /// Stag jump.
+    StagJump, "-Stag", "Stag Jump", "Stag jump, a split jump with the leading leg bent", "LBI-Stag", "Stag",
+    Shape { take_off: code!(LBI), toe: true, hop: code!(RF), exit: code!(LFO) }
+}
+
+minor_jump! {
+    /// Walley jump.
+    Walley, "-Walley", "Walley", "Walley jump, taking off from a back inside edge and rotating against it",
+    "RBI-Walley", "Walley",
+    Shape { take_off: code!(RBI), toe: false, hop: code!(RB), exit: code!(RBO) }
+}
+
+minor_jump! {
+    /// Toe Walley jump.
+    ToeWalley, "-TWalley", "Toe Walley", "Toe Walley jump, a Walley with a toe pick assist", "RBI-TWalley",
+    "Toe Walley",
+    Shape { take_off: code!(RBI), toe: true, hop: code!(RB), exit: code!(RBO) }
+}
+
+minor_jump! {
+    /// Bunny hop.
+    BunnyHop, "-Bunny", "Bunny Hop", "Bunny hop, landing on the opposite toe and stepping forward", "LFO-Bunny",
+    "Bunny hop",
+    Shape { take_off: code!(LFO), toe: false, hop: code!(RF), exit: code!(LFO) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minor_jump_landings() {
+        let tests = [
+            (WaltzJump::SHAPE, code!(RB), code!(RBO)),
+            (Euler::SHAPE, code!(LB), code!(LBI)),
+            (HalfFlip::SHAPE, code!(RF), code!(LFO)),
+            (HalfLutz::SHAPE, code!(RF), code!(LFO)),
+            (SplitJump::SHAPE, code!(RF), code!(LFO)),
+            (StagJump::SHAPE, code!(RF), code!(LFO)),
+            (Walley::SHAPE, code!(RB), code!(RBO)),
+            (ToeWalley::SHAPE, code!(RB), code!(RBO)),
+            (BunnyHop::SHAPE, code!(RF), code!(LFO)),
+        ];
+        for (shape, hop, exit) in tests {
+            assert_eq!(shape.hop, hop, "for take-off {}", shape.take_off);
+            assert_eq!(shape.exit, exit, "for take-off {}", shape.take_off);
+        }
+    }
+}
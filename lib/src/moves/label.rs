@@ -74,6 +74,8 @@ impl Label {
             return Err(ParseError {
                 pos: text_pos,
                 msg: format!("Missing expected prefix {}", Self::INFO.name),
+                file: None,
+                calls: Vec::new(),
             });
         };
         let params = params::populate(Self::INFO.params, rest, text_pos)?;
@@ -144,7 +146,7 @@ impl Move for Label {
             code: None,
         };
         let pos = *start + delta;
-        let mut text = Text::new(self.text.clone())
+        let mut text = Text::new(opts.label_text(self.text.clone()))
             .set("x", pos.pos.x.0)
             .set("y", pos.pos.y.0)
             .set(
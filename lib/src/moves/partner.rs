@@ -0,0 +1,181 @@
+// Copyright 2025 David Drysdale
+
+//! Pseudo-move definition for a partner track that follows the current skater.
+
+use crate::{
+    moves::{self, MoveId, PseudoMoveId},
+    param, params,
+    params::Value,
+    Bounds, Document, Move, MoveParam, ParseError, Position, RenderOptions, Skater, SvgId,
+    TextPosition,
+};
+use std::borrow::Cow;
+
+#[derive(Debug, Clone)]
+pub struct Partner {
+    text_pos: TextPosition,
+    /// Offset of the partner relative to the current skater, in the skater's frame.
+    offset: Position,
+    /// Whether the partner skates on the opposite foot.
+    opposite: bool,
+    colour: Option<String>,
+    pub enabled: bool,
+}
+
+impl Partner {
+    pub const MOVE: &'static str = "Partner";
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::Partner),
+        summary: "Automatically generate a partner track, offset from the current skater's track",
+        example: "Partner[side=-60,opposite=true,colour=\"red\"]",
+        visible: false,
+        params: &[
+            params::Info {
+                name: "fwd",
+                doc: "Distance that the partner is ahead of the skater, in centimetres",
+                default: Value::Number(0),
+                range: params::Range::Any,
+                short: None,
+            },
+            params::Info {
+                name: "side",
+                doc: "Distance that the partner is to the skater's left, in centimetres",
+                default: Value::Number(60),
+                range: params::Range::Any,
+                short: None,
+            },
+            params::Info {
+                name: "opposite",
+                doc: "Whether the partner skates on the opposite foot, which is reflected in edge labels",
+                default: Value::Boolean(false),
+                range: params::Range::Boolean,
+                short: None,
+            },
+            params::Info {
+                name: "colour",
+                doc: "Stroke colour for the partner track; empty to use the skater's colour",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
+            params::Info {
+                name: "enabled",
+                doc: "Whether to generate the partner track for subsequent moves",
+                default: Value::Boolean(true),
+                range: params::Range::Boolean,
+                short: None,
+            },
+        ],
+    };
+
+    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        let colour = params[3].value.as_str(text_pos)?;
+        Ok(Self {
+            text_pos,
+            offset: Position::from_params(&params[1], &params[0], text_pos)?,
+            opposite: params[2].value.as_bool(text_pos)?,
+            colour: if colour.is_empty() {
+                None
+            } else {
+                Some(colour.to_string())
+            },
+            enabled: params[4].value.as_bool(text_pos)?,
+        })
+    }
+
+    /// Return the partner's position given the position of the skater.
+    pub fn skater_for(&self, skater: &Skater) -> Skater {
+        Skater {
+            pos: skater.pos.add_rotated(skater.dir, self.offset),
+            dir: skater.dir,
+            code: if self.opposite {
+                skater.code.opposite()
+            } else {
+                skater.code
+            },
+        }
+    }
+
+    /// Render the partner's version of a move that the skater performs from `skater`.
+    pub fn render_for(
+        &self,
+        doc: Document,
+        mv: &dyn Move,
+        skater: &Skater,
+        opts: &mut RenderOptions,
+    ) -> Document {
+        let partner = self.skater_for(skater);
+        let saved = (
+            opts.count.take(),
+            opts.duration.take(),
+            opts.colour.clone(),
+            opts.swap_feet,
+        );
+        if self.colour.is_some() {
+            opts.colour.clone_from(&self.colour);
+        }
+        opts.swap_feet = self.opposite;
+        let doc = mv.render(doc, &partner, opts, None);
+        (opts.count, opts.duration, opts.colour, opts.swap_feet) = saved;
+        doc
+    }
+}
+
+impl Move for Partner {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::Partner)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![
+            param!("fwd" = (self.offset.y.0 as i32)),
+            param!("side" = (self.offset.x.0 as i32)),
+            param!(self.opposite),
+            param!("colour" = (self.colour.clone().unwrap_or_default())),
+            param!(self.enabled),
+        ]
+    }
+    fn text(&self) -> String {
+        let params = params::to_string(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn expanded_text(&self) -> String {
+        let params = params::to_expanded(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn bounds(&self, _before: &Skater) -> Option<Bounds> {
+        None
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &Skater,
+        _opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        doc
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        // A mirrored pattern has the partner on the other side.
+        Box::new(Self {
+            offset: Position {
+                x: -self.offset.x,
+                y: self.offset.y,
+            },
+            text_pos: self.text_pos.at_repeat(repeat),
+            ..self.clone()
+        })
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+    fn as_partner(&self) -> Option<&Partner> {
+        Some(self)
+    }
+}
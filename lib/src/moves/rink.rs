@@ -6,11 +6,95 @@ use crate::{
     moves::{self, MoveId, PseudoMoveId},
     param, params,
     params::Value,
-    path, Bounds, Centimetres, Move, MoveParam, ParseError, Position, RenderOptions, Skater, SvgId,
-    TextPosition,
+    path, use_at, Bounds, Centimetres, Code, Direction, Document, Move, MoveParam, ParseError,
+    Position, RenderOptions, Rotation, Skater, SvgId, TextPosition,
 };
+use std::borrow::Cow;
 use svg::node::element::{Circle, ClipPath, Group, Rectangle};
 
+/// Standard rink layout.
+#[derive(Debug, PartialEq, Eq)]
+struct Preset {
+    /// Name of the preset.
+    name: &'static str,
+    /// Rink width in centimetres.
+    width: i32,
+    /// Rink length in centimetres.
+    length: i32,
+    /// Diameter of the centre circle in centimetres, 0 to omit.
+    centre_circle: i32,
+    /// Location of mid-lines in centimetres from the centre line, 0 to omit.
+    mid_lines: i32,
+    /// Location of goal lines in centimetres from the ends, 0 to omit.
+    goal_lines: i32,
+}
+
+/// Available rink presets.
+static PRESETS: &[Preset] = &[
+    // ISU/IIHF/Olympic 60m x 30m.
+    Preset {
+        name: "isu",
+        width: 3000,
+        length: 6000,
+        centre_circle: 450,
+        mid_lines: 750,
+        goal_lines: 400,
+    },
+    // NHL 200ft x 85ft.
+    Preset {
+        name: "nhl",
+        width: 2591,
+        length: 6096,
+        centre_circle: 457,
+        mid_lines: 762,
+        goal_lines: 335,
+    },
+    // Small community rink, 40m x 20m, with no hockey lines.
+    Preset {
+        name: "community",
+        width: 2000,
+        length: 4000,
+        centre_circle: 300,
+        mid_lines: 0,
+        goal_lines: 0,
+    },
+];
+
+/// Names of the available presets, in the same order as [`PRESETS`], preceded by the empty string for no preset.
+const PRESET_NAMES: &[&str] = &["", "isu", "nhl", "community"];
+
+// Standard positions and sizes of hockey markings, in centimetres, for a rink of the default size.  Positions are
+// scaled to match the actual size of the rink.
+/// Standard length of the rink, along its long axis.
+const STD_LENGTH: f64 = 6100.0;
+/// Standard width of the rink, across its long axis.
+const STD_WIDTH: f64 = 3000.0;
+/// Standard distance of the goal line from the end of the rink.
+const GOAL_LINE: f64 = 400.0;
+/// Radius of the goal crease.
+const CREASE_RADIUS: f64 = 180.0;
+/// Width of the goal frame.
+const GOAL_WIDTH: f64 = 183.0;
+/// Depth of the goal frame, behind the goal line.
+const GOAL_DEPTH: f64 = 112.0;
+/// Radius of an end-zone face-off circle.
+const FACEOFF_RADIUS: f64 = 450.0;
+/// Radius of a face-off spot.
+const FACEOFF_SPOT_RADIUS: f64 = 30.0;
+/// Distance of the end-zone face-off spots from the goal line, towards the centre of the rink.
+const FACEOFF_FROM_GOAL_LINE: f64 = 600.0;
+/// Distance of the face-off spots from the long axis of the rink.
+const FACEOFF_FROM_AXIS: f64 = 700.0;
+/// Length of a hash mark on an end-zone face-off circle.
+const HASH_LENGTH: f64 = 60.0;
+/// Distance between the pair of hash marks on each side of an end-zone face-off circle.
+const HASH_SEPARATION: f64 = 170.0;
+/// Distance of the neutral-zone face-off spots from the mid-lines, towards the centre of the rink.
+const NEUTRAL_FROM_MID_LINE: f64 = 150.0;
+/// Standard distance of the mid-lines from the centre line, used to position the neutral-zone face-off spots when
+/// there are no mid-lines.
+const MID_LINE: f64 = 800.0;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Rink {
     text_pos: TextPosition,
@@ -23,6 +107,13 @@ pub struct Rink {
     goal_lines: Option<Centimetres>,
     show_goals: bool,
     show_faceoffs: bool,
+    preset: Option<&'static Preset>,
+    /// Position of the top-left corner of the rink.
+    pos: Position,
+    /// Rotation of the rink about its top-left corner.
+    rotate: Rotation,
+    /// Colour for moves that go outside the rink, if they are to be highlighted.
+    highlight: Option<String>,
 }
 
 impl Rink {
@@ -97,11 +188,59 @@ impl Rink {
                 range: params::Range::Boolean,
                 short: None,
             },
+            params::Info {
+                name: "preset",
+                doc: "Standard rink layout: \"isu\" (60m x 30m), \"nhl\" (200ft x 85ft) or \"community\" (40m x 20m); supplies the size and lines of the rink for any of those parameters that are left at their defaults",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Choice(PRESET_NAMES),
+                short: None,
+            },
+            params::Info {
+                name: "x",
+                doc: "Horizontal position of the top-left corner of the rink, in centimetres relative to the start of the pattern",
+                default: Value::Number(0),
+                range: params::Range::Any,
+                short: None,
+            },
+            params::Info {
+                name: "y",
+                doc: "Vertical position of the top-left corner of the rink, in centimetres relative to the start of the pattern",
+                default: Value::Number(0),
+                range: params::Range::Any,
+                short: None,
+            },
+            params::Info {
+                name: "rotate",
+                doc: "Clockwise rotation of the rink about its top-left corner, in degrees",
+                default: Value::Number(0),
+                range: params::Range::Any,
+                short: None,
+            },
+            params::Info {
+                name: "highlight",
+                doc: "Colour to draw moves that go outside the rink in; empty for no highlighting",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
         ],
     };
 
-    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+    pub fn from_params(
+        text_pos: TextPosition,
+        mut params: Vec<MoveParam>,
+    ) -> Result<Self, ParseError> {
         assert!(params::compatible(Self::INFO.params, &params));
+        let name = params[9].value.as_str(text_pos)?;
+        let preset = PRESETS.iter().find(|preset| preset.name == name);
+        if let Some(preset) = preset {
+            // The preset supplies any of its values that have been left at the default.
+            for (idx, val) in Self::preset_values(preset) {
+                if params[idx].value == Self::INFO.params[idx].default {
+                    params[idx].value = Value::Number(val);
+                }
+            }
+        }
         let to_opt_cm = |param: &MoveParam| {
             let val = param.value.as_i32(text_pos).unwrap();
             if val > 0 {
@@ -121,20 +260,263 @@ impl Rink {
             goal_lines: to_opt_cm(&params[6]),
             show_goals: params[7].value.as_bool(text_pos)?,
             show_faceoffs: params[8].value.as_bool(text_pos)?,
+            preset,
+            pos: Position {
+                x: params[10].value.as_cm(text_pos)?,
+                y: params[11].value.as_cm(text_pos)?,
+            },
+            rotate: Rotation(params[12].value.as_i32(text_pos)?),
+            highlight: Some(params[13].value.as_str(text_pos)?.to_string())
+                .filter(|colour| !colour.is_empty()),
         })
     }
 
+    /// Parameters with their actual values, including any supplied by a preset.
+    fn actual_params(&self) -> Vec<MoveParam> {
+        let from_opt_cm = |val: Option<Centimetres>| val.map(|v| v.0 as i32).unwrap_or(0);
+        vec![
+            param!("width" = self.width.0 as i32),
+            param!("length" = self.length.0 as i32),
+            param!("centre-line" = self.centre_line),
+            param!("centre-circle" = from_opt_cm(self.centre_circle)),
+            param!("centre-faceoff" = self.centre_faceoff),
+            param!("mid-lines" = from_opt_cm(self.mid_lines)),
+            param!("goal-lines" = from_opt_cm(self.goal_lines)),
+            param!("goals" = self.show_goals),
+            param!("faceoffs" = self.show_faceoffs),
+            param!("preset" = self.preset.map(|preset| preset.name).unwrap_or("")),
+            param!("x" = self.pos.x.0 as i32),
+            param!("y" = self.pos.y.0 as i32),
+            param!("rotate" = self.rotate.0),
+            param!("highlight" = self.highlight.clone().unwrap_or_default()),
+        ]
+    }
+
+    /// Rink with all parameters at their default values.
+    pub(crate) fn standard(text_pos: TextPosition) -> Self {
+        let params = Self::INFO
+            .params
+            .iter()
+            .map(|info| MoveParam {
+                name: info.name,
+                value: info.default.clone(),
+            })
+            .collect();
+        Self::from_params(text_pos, params).expect("default rink parameters are valid")
+    }
+
+    /// Width and length of the rink.
+    pub(crate) fn size(&self) -> (Centimetres, Centimetres) {
+        (self.width, self.length)
+    }
+
+    /// Colour to draw moves that go outside the rink in, if any.
+    pub fn highlight(&self) -> Option<&str> {
+        self.highlight.as_deref()
+    }
+
+    /// Indicate whether any part of the path of `mv`, starting from `start`, goes outside the rink.
+    pub fn outside(&self, mv: &dyn Move, start: &Skater) -> bool {
+        self.outside_path(&mv.path_points(), start)
+    }
+
+    /// Indicate whether any of the path `points` (relative to (0,0) at 0°), starting from `start`, are outside the
+    /// rink.
+    pub fn outside_path(&self, points: &[Position], start: &Skater) -> bool {
+        points
+            .iter()
+            .any(|pt| !self.contains(start.pos.add_rotated(start.dir, *pt)))
+    }
+
+    /// Indicate whether the given diagram position is within the rounded rectangle of the rink.
+    fn contains(&self, pos: Position) -> bool {
+        // Convert to coordinates relative to the top-left corner of the unrotated rink.
+        let dx = (pos.x - self.pos.x).0 as f64;
+        let dy = (pos.y - self.pos.y).0 as f64;
+        let theta = self.rotate.radians();
+        let x = dx * theta.cos() + dy * theta.sin();
+        let y = dy * theta.cos() - dx * theta.sin();
+
+        let (width, length) = (self.width.0 as f64, self.length.0 as f64);
+        if x < 0.0 || y < 0.0 || x > width || y > length {
+            return false;
+        }
+        // Within the bounding rectangle, so only the rounded corners can be outside the rink.
+        let r = self.rounding().0 as f64;
+        let cx = x.clamp(r, width - r);
+        let cy = y.clamp(r, length - r);
+        (x - cx).powi(2) + (y - cy).powi(2) <= r * r
+    }
+
+    /// Values supplied by a preset, with the index of the parameter they apply to.
+    fn preset_values(preset: &Preset) -> [(usize, i32); 5] {
+        [
+            (0, preset.width),
+            (1, preset.length),
+            (3, preset.centre_circle),
+            (5, preset.mid_lines),
+            (6, preset.goal_lines),
+        ]
+    }
+
+    /// Position and direction of the top-left corner of the rink.
+    pub(crate) fn corner(&self, code: Code) -> Skater {
+        Skater {
+            pos: self.pos,
+            dir: Direction::new(self.rotate.0),
+            code,
+        }
+    }
+
     fn rounding(&self) -> Centimetres {
         let dim = std::cmp::min(self.width, self.length);
         std::cmp::min(dim / 4, Centimetres(850))
     }
-    fn portrait(&self) -> bool {
+    pub(crate) fn portrait(&self) -> bool {
         self.width < self.length
     }
     #[allow(dead_code)]
     fn landscape(&self) -> bool {
         self.width > self.length
     }
+    /// Identifier for the clip path of the rink, derived from its parameters.
+    fn clip_id(&self) -> String {
+        let params: String = self
+            .text()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        format!("clip-{}", params.trim_end_matches('-'))
+    }
+    /// Size of the rink along its long axis.
+    fn long(&self) -> f64 {
+        std::cmp::max(self.width, self.length).0 as f64
+    }
+    /// Size of the rink across its long axis.
+    fn short(&self) -> f64 {
+        std::cmp::min(self.width, self.length).0 as f64
+    }
+    /// Scale factor for the sizes of markings.
+    fn scale(&self) -> f64 {
+        f64::min(self.long() / STD_LENGTH, self.short() / STD_WIDTH)
+    }
+    /// Convert a position given as a distance `across` the rink and a distance `along` from one `end` of the rink
+    /// into diagram coordinates.
+    fn at(&self, end: End, across: f64, along: f64) -> (i64, i64) {
+        let along = match end {
+            End::Near => along,
+            End::Far => self.long() - along,
+        };
+        if self.portrait() {
+            (across.round() as i64, along.round() as i64)
+        } else {
+            (along.round() as i64, across.round() as i64)
+        }
+    }
+    /// Distance of the goal line from the end of the rink.
+    fn goal_line(&self) -> f64 {
+        match self.goal_lines {
+            Some(dist) => dist.0 as f64,
+            None => GOAL_LINE * self.long() / STD_LENGTH,
+        }
+    }
+    /// Distances across the rink of the face-off spots on either side of the long axis.
+    fn faceoff_across(&self) -> [f64; 2] {
+        let offset = FACEOFF_FROM_AXIS * self.short() / STD_WIDTH;
+        [self.short() / 2.0 - offset, self.short() / 2.0 + offset]
+    }
+
+    fn spot(&self, end: End, across: f64, along: f64) -> Circle {
+        let (cx, cy) = self.at(end, across, along);
+        Circle::new()
+            .set("cx", cx)
+            .set("cy", cy)
+            .set("r", (FACEOFF_SPOT_RADIUS * self.scale()).round() as i64)
+            .set("style", "stroke:red; fill:red;")
+    }
+
+    /// Render the goal crease and goal frame at one end of the rink.
+    fn goal(&self, mut grp: Group, end: End) -> Group {
+        let scale = self.scale();
+        let centre = self.short() / 2.0;
+        let line = self.goal_line();
+
+        // The crease is a semicircle on the goal line, bulging towards the centre of the rink.  Mirroring the crease,
+        // either by swapping the axes or by moving to the far end, reverses the sweep direction of the arc.
+        let radius = (CREASE_RADIUS * scale).round() as i64;
+        let (x0, y0) = self.at(end, centre - CREASE_RADIUS * scale, line);
+        let (x1, y1) = self.at(end, centre + CREASE_RADIUS * scale, line);
+        let sweep = u8::from(self.portrait() == (end == End::Far));
+        grp = grp.add(
+            path!("M {x0},{y0} A {radius},{radius} 0 0 {sweep} {x1},{y1}")
+                .set("style", "stroke:red;"),
+        );
+
+        // The goal frame sits behind the goal line.
+        let half_width = GOAL_WIDTH * scale / 2.0;
+        let back = line - GOAL_DEPTH * scale;
+        let (x0, y0) = self.at(end, centre - half_width, line);
+        let (x1, y1) = self.at(end, centre - half_width, back);
+        let (x2, y2) = self.at(end, centre + half_width, back);
+        let (x3, y3) = self.at(end, centre + half_width, line);
+        grp.add(
+            path!("M {x0},{y0} L {x1},{y1} L {x2},{y2} L {x3},{y3}").set("style", "stroke:red;"),
+        )
+    }
+
+    /// Render the face-off circles and spots in the end zone at one end of the rink.
+    fn end_faceoffs(&self, mut grp: Group, end: End) -> Group {
+        let scale = self.scale();
+        let radius = FACEOFF_RADIUS * scale;
+        let along = self.goal_line() + FACEOFF_FROM_GOAL_LINE * self.long() / STD_LENGTH;
+        for across in self.faceoff_across() {
+            let (cx, cy) = self.at(end, across, along);
+            grp = grp
+                .add(
+                    Circle::new()
+                        .set("cx", cx)
+                        .set("cy", cy)
+                        .set("r", radius.round() as i64)
+                        .set("style", "stroke:red;"),
+                )
+                .add(self.spot(end, across, along));
+
+            // Hash marks extend outwards from each side of the circle, parallel to the goal line.
+            let half_sep = HASH_SEPARATION * scale / 2.0;
+            let inner = (radius * radius - half_sep * half_sep).sqrt();
+            let outer = inner + HASH_LENGTH * scale;
+            for side in [-1.0, 1.0] {
+                for hash in [along - half_sep, along + half_sep] {
+                    let (x0, y0) = self.at(end, across + side * inner, hash);
+                    let (x1, y1) = self.at(end, across + side * outer, hash);
+                    grp = grp.add(path!("M {x0},{y0} L {x1},{y1}").set("style", "stroke:red;"));
+                }
+            }
+        }
+        grp
+    }
+
+    /// Render the face-off spots in the neutral zone on one side of the centre line.
+    fn neutral_faceoffs(&self, mut grp: Group, end: End) -> Group {
+        let mid_line = match self.mid_lines {
+            Some(dist) => dist.0 as f64,
+            None => MID_LINE * self.long() / STD_LENGTH,
+        };
+        let along = self.long() / 2.0 - mid_line + NEUTRAL_FROM_MID_LINE * self.long() / STD_LENGTH;
+        for across in self.faceoff_across() {
+            grp = grp.add(self.spot(end, across, along));
+        }
+        grp
+    }
+}
+
+/// End of the rink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    /// End of the rink at the origin.
+    Near,
+    /// End of the rink away from the origin.
+    Far,
 }
 
 impl Move for Rink {
@@ -142,38 +524,45 @@ impl Move for Rink {
         MoveId::Pseudo(PseudoMoveId::Rink)
     }
     fn params(&self) -> Vec<MoveParam> {
-        let from_opt_cm = |val: Option<Centimetres>| val.map(|v| v.0 as i32).unwrap_or(0);
-        vec![
-            param!("width" = self.width.0 as i32),
-            param!("length" = self.length.0 as i32),
-            param!("centre-line" = self.centre_line),
-            param!("centre-circle" = from_opt_cm(self.centre_circle)),
-            param!("centre-faceoff" = self.centre_faceoff),
-            param!("mid-lines" = from_opt_cm(self.mid_lines)),
-            param!("goal-lines" = from_opt_cm(self.goal_lines)),
-            param!("goals" = self.show_goals),
-            param!("faceoffs" = self.show_faceoffs),
-        ]
+        let mut params = self.actual_params();
+        if let Some(preset) = self.preset {
+            // Values that match the preset are left at their defaults.
+            for (idx, val) in Self::preset_values(preset) {
+                if params[idx].value == Value::Number(val) {
+                    params[idx].value = Self::INFO.params[idx].default.clone();
+                }
+            }
+        }
+        params
     }
     fn text(&self) -> String {
         let params = params::to_string(Self::INFO.params, &self.params());
         format!("{}{params}", Self::INFO.name)
     }
     fn expanded_text(&self) -> String {
-        let params = params::to_expanded(Self::INFO.params, &self.params());
+        let params = params::to_expanded(Self::INFO.params, &self.actual_params());
         format!("{}{params}", Self::INFO.name)
     }
     fn text_pos(&self) -> Option<TextPosition> {
         Some(self.text_pos)
     }
-    fn bounds(&self, _before: &Skater) -> Option<Bounds> {
-        Some(Bounds {
-            top_left: crate::ORIGIN,
-            bottom_right: Position {
-                x: self.width,
-                y: self.length,
-            },
-        })
+    fn bounds(&self, before: &Skater) -> Option<Bounds> {
+        // The rink is placed relative to the start of the pattern, regardless of the current skater position.
+        let corner = self.corner(before.code);
+        let mut bounds = Bounds {
+            top_left: corner.pos,
+            bottom_right: corner.pos,
+        };
+        let zero = Centimetres(0);
+        for (x, y) in [
+            (self.width, zero),
+            (zero, self.length),
+            (self.width, self.length),
+        ] {
+            let pt = corner + Position { x, y };
+            bounds.encompass(&pt.pos);
+        }
+        Some(bounds)
     }
     fn defs(&self, _opts: &mut RenderOptions) -> Vec<(SvgId, Group)> {
         let rink_rect = Rectangle::new()
@@ -183,13 +572,14 @@ impl Move for Rink {
             .set("height", self.length.0)
             .set("rx", self.rounding().0)
             .set("ry", self.rounding().0);
-        let clip_path = ClipPath::new() // TODO fix clip-path
-            .set("id", "clip-rink")
+        // All markings are clipped to the rounded rectangle of the boards.  The clip path is shared by every use of
+        // the same rink definition, but needs a distinct identifier from the clip paths of any other rinks.
+        let clip_id = self.clip_id();
+        let clip_path = ClipPath::new()
+            .set("id", clip_id.clone())
             .add(rink_rect.clone());
-        let mut grp = Group::new().add(clip_path).add(rink_rect.set(
-            "style",
-            format!("{} clip-path:url(#clip-rink)", crate::STYLE_DEF),
-        ));
+        let outline = Group::new().add(clip_path).add(rink_rect);
+        let mut grp = Group::new().set("clip-path", format!("url(#{clip_id})"));
         if self.centre_line {
             if self.portrait() {
                 grp = grp.add(
@@ -221,7 +611,6 @@ impl Move for Rink {
                     .set("style", "fill: black;"),
             )
         }
-        // TODO: mid and goal lines need to clip to the rounded-rectangle
         if let Some(dist) = self.mid_lines {
             if self.portrait() {
                 grp = grp
@@ -256,9 +645,34 @@ impl Move for Rink {
                     .add(path!("M {0},0 l 0,{1}", self.width - dist, self.length));
             }
         }
-        // TODO: render `show_goals`
-        // TODO: render `show_faceoffs`
-        vec![(SvgId(self.text()), grp)]
+        for end in [End::Near, End::Far] {
+            if self.show_goals {
+                grp = self.goal(grp, end);
+            }
+            if self.show_faceoffs {
+                grp = self.end_faceoffs(grp, end);
+                grp = self.neutral_faceoffs(grp, end);
+            }
+        }
+        vec![(SvgId(self.text()), outline.add(grp))]
+    }
+    fn render(
+        &self,
+        doc: Document,
+        start: &Skater,
+        opts: &mut RenderOptions,
+        ns: Option<&SvgId>,
+    ) -> Document {
+        let def_id = SvgId(self.text());
+        let def_id = match ns {
+            Some(outer) => def_id.in_ns(outer),
+            None => def_id,
+        };
+        let unique_id = opts.next_unique_id(self.text_pos);
+        doc.add(use_at(&self.corner(start.code), &def_id, opts).set("id", unique_id))
+    }
+    fn as_rink(&self) -> Option<&Rink> {
+        Some(self)
     }
     fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
         self.box_clone(repeat)
@@ -68,6 +68,8 @@ impl Shift {
             return Err(ParseError {
                 pos: text_pos,
                 msg: format!("Missing expected prefix {}", Self::INFO.name),
+                file: None,
+                calls: Vec::new(),
             });
         };
         let params = params::populate(Self::INFO.params, rest, text_pos)?;
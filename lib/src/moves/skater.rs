@@ -0,0 +1,105 @@
+// Copyright 2025 David Drysdale
+
+//! Pseudo-move definition for switching between skaters.
+
+use crate::{
+    moves::{self, MoveId, PseudoMoveId},
+    param, params,
+    params::Value,
+    Bounds, Document, Move, MoveParam, ParseError, RenderOptions, SvgId, TextPosition,
+};
+use std::borrow::Cow;
+
+#[derive(Debug, Clone)]
+pub struct Skater {
+    text_pos: TextPosition,
+    pub name: String,
+    pub colour: Option<String>,
+}
+
+impl Skater {
+    pub const MOVE: &'static str = "Skater";
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::Skater),
+        summary: "Switch to a named skater, who has their own independent track",
+        example: "Skater[name=\"Lady\",colour=\"red\"]",
+        visible: false,
+        params: &[
+            params::Info {
+                name: "name",
+                doc: "Name of the skater that subsequent moves apply to",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
+            params::Info {
+                name: "colour",
+                doc: "Stroke colour for the skater's track; empty to keep the current colour",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
+        ],
+    };
+
+    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        let colour = params[1].value.as_str(text_pos)?;
+        Ok(Self {
+            text_pos,
+            name: params[0].value.as_str(text_pos)?.to_string(),
+            colour: if colour.is_empty() {
+                None
+            } else {
+                Some(colour.to_string())
+            },
+        })
+    }
+}
+
+impl Move for Skater {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::Skater)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![
+            param!(self.name),
+            param!("colour" = (self.colour.clone().unwrap_or_default())),
+        ]
+    }
+    fn text(&self) -> String {
+        let params = params::to_string(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn expanded_text(&self) -> String {
+        let params = params::to_expanded(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn bounds(&self, _before: &crate::Skater) -> Option<Bounds> {
+        None
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &crate::Skater,
+        _opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        doc
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        self.box_clone(repeat)
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+    fn as_skater(&self) -> Option<&Skater> {
+        Some(self)
+    }
+}
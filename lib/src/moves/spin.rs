@@ -0,0 +1,343 @@
+// Copyright 2025 David Drysdale
+
+//! Spins
+
+use super::{
+    compound::{map_errs, Compound},
+    edge::Curve,
+    edge_err,
+    shift::Shift,
+    MoveId, SkatingMoveId,
+};
+use crate::{
+    cm, code, moves, param, params, params::Value, Bounds, Centimetres, Code, Direction, Edge,
+    Foot, Label, Move, MoveParam, ParseError, Percentage, Position, PreTransition, RenderOptions,
+    Rotation, Skater, SkatingDirection, SpatialTransition, SpinKind, SpinPosition, SvgId,
+    TextPosition, Transition,
+};
+use std::borrow::Cow;
+use svg::node::element::Text as SvgText;
+use svg::node::element::{Circle, Group};
+
+/// Radius of the spin-centre symbol for a spin with no revolutions.
+const BASE_RADIUS: Centimetres = cm!(20);
+/// Additional radius of the spin-centre symbol for each revolution.
+const RADIUS_PER_REV: Centimetres = cm!(5);
+/// Gap between the take-off and the spin centre for a flying entry.
+const FLYING_GAP: Centimetres = cm!(150);
+/// Distance between the two spin centres for a change-foot spin.
+const CHANGE_GAP: Centimetres = cm!(30);
+
+/// Spin.
+pub struct Spin;
+
+impl Spin {
+    /// Move code suffix.
+    pub const MOVE: &'static str = "Sp";
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: "Spin",
+        id: MoveId::Skating(SkatingMoveId::Spin(SpinKind {
+            position: SpinPosition::Upright,
+            flying: false,
+            change_foot: false,
+        })),
+        summary: "Spin, coded as [F][C]{U,S,C,L,Co}Sp for flying/change-foot upright/sit/camel/layback/combination",
+        example: "LFO-CCoSp",
+        visible: true,
+        params: &[
+            params::Info {
+                name: "revs",
+                doc: "Number of revolutions, reflected in the size of the spin symbol",
+                default: Value::Number(6),
+                range: params::Range::StrictlyPositive,
+                short: Some(params::Abbrev::PlusMinus(params::Detents {
+                    add1: 8,
+                    add2: 10,
+                    add3: 12,
+                    less1: 4,
+                    less2: 3,
+                    less3: 2,
+                })),
+            },
+            params::Info {
+                name: "positions",
+                doc: "Sequence of positions to annotate the spin with, e.g. \"C-S-U\"",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
+            params::Info {
+                name: "spin-label",
+                doc: "Replacement spin label, used if non-empty",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
+            params::Info {
+                name: "entry-angle",
+                doc: "Angle of rotation for entry edge, in degrees",
+                default: Value::Number(30),
+                range: params::Range::StrictlyPositive,
+                short: Some(params::Abbrev::GreaterLess(params::Detents {
+                    add1: 45,
+                    add2: 60,
+                    add3: 80,
+                    less1: 25,
+                    less2: 20,
+                    less3: 15,
+                })),
+            },
+            params::Info {
+                name: "entry-len",
+                doc: "Length of entry edge, in centimetres",
+                default: Value::Number(450),
+                range: params::Range::StrictlyPositive,
+                short: None,
+            },
+            params::Info {
+                name: "exit-angle",
+                doc: "Angle of rotation for exit edge, in degrees",
+                default: Value::Number(40),
+                range: params::Range::Any,
+                short: None,
+            },
+            params::Info {
+                name: "exit-len",
+                doc: "Length of exit edge, in centimetres",
+                default: Value::Number(400),
+                range: params::Range::Any,
+                short: None,
+            },
+            params::Info {
+                name: "style",
+                doc: "Style of line",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
+            params::Info {
+                name: "label-offset",
+                doc: "Amount to scale label offsets by, as a percentage, or -1 to use global value",
+                default: Value::Number(-1),
+                range: params::Range::Any,
+                short: None,
+            },
+        ],
+    };
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_params(
+        input: &str,
+        text_pos: TextPosition,
+        pre_transition: PreTransition,
+        entry_code: Code,
+        kind: SpinKind,
+        params: Vec<MoveParam>,
+        ctx: &mut moves::Context,
+    ) -> Result<Compound, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        let regular = match entry_code {
+            code!(LFO) => true,
+            code!(RFO) => false,
+            _ => return Err(edge_err(text_pos, entry_code, Self::INFO)),
+        };
+        ctx.prev_label = None;
+
+        let revs = params[0].value.as_i32(text_pos)? as u32;
+        let positions = params[1].value.as_str(text_pos)?;
+        let spin_label = params[2].value.as_str(text_pos)?;
+        let entry_angle = params[3].value.as_rotation(text_pos)?;
+        let entry_len = params[4].value.as_cm(text_pos)?;
+        let exit_angle = params[5].value.as_rotation(text_pos)?;
+        let exit_len = params[6].value.as_cm(text_pos)?;
+        let style = params[7].value.as_str(text_pos)?;
+        let label_offset = params[8].value.as_percent(text_pos)?;
+
+        let prefix = pre_transition.prefix();
+        let out_code = if regular { code!(RBO) } else { code!(LBO) };
+        let sign = if regular { "-" } else { "" };
+
+        let label = if !spin_label.is_empty() {
+            spin_label.to_string()
+        } else if !positions.is_empty() {
+            format!("{kind} ({positions})")
+        } else {
+            format!("{kind}")
+        };
+
+        let entry = format!("{prefix}{entry_code}[angle={entry_angle},len={entry_len},style=\"{style}\",label-offset={label_offset}]");
+        let mut moves = vec![Curve::construct(&entry, text_pos)];
+
+        // Split the revolutions between the feet for a change-foot spin.
+        let (revs1, revs2) = if kind.change_foot {
+            (revs.div_ceil(2), revs / 2)
+        } else {
+            (revs, 0)
+        };
+        let radius1 = Centre::radius(revs1);
+        let approach = if kind.flying {
+            FLYING_GAP + radius1
+        } else {
+            radius1
+        };
+        moves.push(Shift::construct(
+            &format!("Shift[fwd={approach}]"),
+            text_pos,
+        ));
+        moves.push(Ok(Box::new(Centre {
+            text_pos,
+            kind,
+            foot: entry_code.foot,
+            revs: revs1,
+            label: Some(label),
+            label_offset,
+        })));
+        let mut radius = radius1;
+        if kind.change_foot {
+            // Step across to spin on the other foot.
+            let radius2 = Centre::radius(revs2);
+            let gap = radius1 + CHANGE_GAP + radius2;
+            moves.push(Shift::construct(
+                &format!("Shift[side={sign}{gap},code=\"{out_code}\"]"),
+                text_pos,
+            ));
+            moves.push(Ok(Box::new(Centre {
+                text_pos,
+                kind,
+                foot: out_code.foot,
+                revs: revs2,
+                label: None,
+                label_offset,
+            })));
+            radius = radius2;
+        }
+        let shift = format!("Shift[side={sign}{radius},rotate={sign}90,code=\"{out_code}\"]");
+        moves.push(Shift::construct(&shift, text_pos));
+        let exit = format!(
+            "{out_code}[angle={exit_angle},len={exit_len},style=\"{style}\",label-offset={label_offset}]"
+        );
+        moves.push(Curve::construct(&exit, text_pos));
+        log::info!("input {input:?} results in {entry};...;{shift};{exit}");
+
+        let text_prefix = format!("{prefix}{entry_code}-{kind}");
+
+        Ok(Compound::new(
+            text_pos,
+            SkatingMoveId::Spin(kind),
+            map_errs(moves)?,
+            Self::INFO.params,
+            params,
+            text_prefix,
+        ))
+    }
+}
+
+/// Spin-centre symbol, for use within a [`Spin`].
+#[derive(Debug, Clone)]
+struct Centre {
+    text_pos: TextPosition,
+    kind: SpinKind,
+    foot: Foot,
+    revs: u32,
+    label: Option<String>,
+    label_offset: Percentage,
+}
+
+impl Centre {
+    /// Radius of the symbol for the given number of revolutions.
+    fn radius(revs: u32) -> Centimetres {
+        BASE_RADIUS + Centimetres(RADIUS_PER_REV.0 * revs as i64)
+    }
+    fn code(&self) -> Code {
+        Code {
+            foot: self.foot,
+            dir: SkatingDirection::Backward,
+            edge: Edge::Flat,
+        }
+    }
+}
+
+impl Move for Centre {
+    fn id(&self) -> MoveId {
+        MoveId::Skating(SkatingMoveId::Spin(self.kind))
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![param!("revs" = self.revs as i32)]
+    }
+    fn start(&self) -> Option<Code> {
+        Some(self.code())
+    }
+    fn text(&self) -> String {
+        format!("{}-{}-Centre[revs={}]", self.code(), self.kind, self.revs)
+    }
+    fn expanded_text(&self) -> String {
+        self.text()
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn transition(&self) -> Transition {
+        Transition {
+            spatial: SpatialTransition::Relative {
+                delta: Position::default(),
+                rotate: Rotation::default(),
+            },
+            code: Some(self.code()),
+        }
+    }
+    fn path_points(&self) -> Vec<Position> {
+        // Points around the edge of the symbol.
+        let radius = Position {
+            x: Centimetres(0),
+            y: Self::radius(self.revs),
+        };
+        (0..8)
+            .map(|n| Position::default().add_rotated(Direction::new(n * 45), radius))
+            .collect()
+    }
+    fn bounds(&self, before: &Skater) -> Option<Bounds> {
+        let radius = Self::radius(self.revs);
+        let mut bounds = Bounds {
+            top_left: before.pos,
+            bottom_right: before.pos,
+        };
+        bounds.add_margin(radius, radius);
+        Some(bounds)
+    }
+    fn defs(&self, _opts: &mut RenderOptions) -> Vec<(SvgId, Group)> {
+        let grp = Group::new()
+            .add(Circle::new().set("r", Self::radius(self.revs).0))
+            .add(Circle::new().set("r", 3).set("style", "fill: black;"));
+        vec![(SvgId(self.text()), grp)]
+    }
+    fn labels(&self, opts: &RenderOptions) -> Vec<Label> {
+        let Some(text) = &self.label else {
+            return Vec::new();
+        };
+        let text = opts.label_text(text.clone());
+        let label_offset_fraction = self.label_offset.for_opts(opts);
+        let font_size = opts.font_size().0 as i64;
+        let dist = cm!((3.0 * font_size as f64 * label_offset_fraction) as i64);
+        vec![Label {
+            display: !text.trim().is_empty(),
+            text: SvgText::new(text),
+            pos: Position {
+                x: Self::radius(self.revs) + dist,
+                y: Centimetres(0),
+            },
+        }]
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        Box::new(Self {
+            foot: self.foot.opposite(),
+            text_pos: self.text_pos.at_repeat(repeat),
+            ..self.clone()
+        })
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+}
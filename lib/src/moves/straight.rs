@@ -10,7 +10,7 @@ use crate::{
     params::Value,
     path, Centimetres, Code, Edge, Foot, Label, Move, MoveParam, ParseError, Position,
     PreTransition, RenderOptions, Rotation, SkatingDirection, SpatialTransition, SvgId,
-    TextPosition, Transition,
+    TextPosition, Transition, WarningKind,
 };
 use std::borrow::Cow;
 use svg::node::element::Group;
@@ -92,6 +92,8 @@ impl StraightEdge {
             return Err(ParseError {
                 pos: text_pos,
                 msg: format!("Entry edge {entry_code} not supported"),
+                file: None,
+                calls: Vec::new(),
             });
         }
         assert!(params::compatible(Self::INFO.params, &params));
@@ -152,6 +154,9 @@ impl Move for StraightEdge {
     fn pre_transition(&self, from: Code) -> Transition {
         self.pre_transition.perform(from, self.code())
     }
+    fn pre_transition_warning(&self, from: Code, strict: bool) -> Option<WarningKind> {
+        self.pre_transition.warning(from, self.code(), strict)
+    }
     fn transition(&self) -> Transition {
         Transition {
             spatial: SpatialTransition::Relative {
@@ -174,14 +179,14 @@ impl Move for StraightEdge {
         path = apply_style(path, &self.style);
         vec![(SvgId(self.text()), Group::new().add(path))]
     }
-    fn labels(&self, _opts: &RenderOptions) -> Vec<Label> {
+    fn labels(&self, opts: &RenderOptions) -> Vec<Label> {
         if self.foot == Foot::Both {
             vec![]
         } else {
-            let text = match &self.label {
+            let text = opts.label_text(match &self.label {
                 Some(label) => label.clone(),
                 None => format!("{}{}", self.foot, self.dir),
-            };
+            });
             vec![Label {
                 display: !text.trim().is_empty(),
                 text: SvgText::new(text),
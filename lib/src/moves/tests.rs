@@ -3,7 +3,8 @@
 //! Unit tests.
 
 use super::*;
-use crate::{code, params::Value, parser};
+use crate::{code, params, params::Value, parser};
+use std::borrow::Cow;
 
 fn check_consistent(mv: &dyn Move, input: &str) {
     assert_eq!(
@@ -45,7 +46,7 @@ fn test_examples_all_params() {
             .iter()
             .map(|info| MoveParam {
                 name: info.name,
-                value: make_non_default(&info.default),
+                value: make_non_default(info),
             })
             .collect();
 
@@ -93,11 +94,16 @@ fn test_examples_all_params() {
     }
 }
 
-fn make_non_default(value: &Value) -> Value {
-    match value {
-        Value::Number(v) => Value::Number(v + 1),
-        Value::Boolean(b) => Value::Boolean(!b),
-        Value::Text(t) => Value::Text(format!("{t}LFO").into()),
+fn make_non_default(info: &params::Info) -> Value {
+    match (&info.default, info.range) {
+        (Value::Text(t), params::Range::Choice(choices)) => {
+            let choice = choices.iter().find(|choice| **choice != t).unwrap();
+            Value::Text(Cow::Borrowed(choice))
+        }
+        (Value::Number(v), _) => Value::Number(v + 1),
+        (Value::Boolean(b), _) => Value::Boolean(!b),
+        (Value::Text(t), _) => Value::Text(format!("{t}LFO").into()),
+        (Value::Expression(e), _) => unreachable!("expression {e} as default value"),
     }
 }
 
@@ -128,3 +134,87 @@ fn test_parse_failures() {
         }
     }
 }
+
+#[test]
+fn test_figure_numbers() {
+    // Every available figure number should construct, starting on either foot (but not on other edges).
+    let params::Range::Choice(numbers) = figure::Figure::INFO.params[0].range else {
+        panic!("figure number is not a choice");
+    };
+    let codes = [
+        code!(LFO),
+        code!(LFI),
+        code!(LBO),
+        code!(LBI),
+        code!(RFO),
+        code!(RFI),
+        code!(RBO),
+        code!(RBI),
+    ];
+    for number in numbers {
+        let mut feet = Vec::new();
+        for code in codes {
+            let input = format!("{code}-Fig[number=\"{number}\"]");
+            let (_rest, mv) = crate::parser::mv::parse_move(&input, &input)
+                .unwrap_or_else(|e| panic!("'{input}' doesn't parse!: {e:?}"));
+            let Ok(mv) = mv.construct(&mut Context::default()) else {
+                continue;
+            };
+            check_consistent(&*mv.mv, &mv.mv.text());
+            feet.push(code.foot);
+        }
+        assert_eq!(feet, vec![Foot::Left, Foot::Right], "for figure {number}");
+    }
+}
+
+#[test]
+fn test_axis_start() {
+    let tests = [
+        // Portrait rink: axis runs down the rink.
+        (
+            "Rink[width=2000,length=4000]",
+            "Axis[start=true]",
+            (1000, 0),
+            0,
+        ),
+        (
+            "Rink[width=2000,length=4000]",
+            "Axis[barrier=500,along=1000,start=true]",
+            (500, 1000),
+            0,
+        ),
+        (
+            "Rink[width=2000,length=4000]",
+            "Axis[barrier=-500,along=1000,reverse=true,start=true]",
+            (1500, 1000),
+            180,
+        ),
+        // Landscape rink: axis runs across the diagram.
+        (
+            "Rink[width=4000,length=2000]",
+            "Axis[barrier=500,along=1000,start=true]",
+            (1000, 500),
+            270,
+        ),
+        // Placed rink.
+        (
+            "Rink[width=2000,length=4000,x=-100,y=-200]",
+            "Axis[barrier=500,along=1000,start=true]",
+            (400, 800),
+            0,
+        ),
+    ];
+    for (rink, input, (x, y), dir) in tests {
+        let mut ctx = Context::default();
+        let [rink_mv, axis_mv] = [rink, input].map(|text| {
+            let (_rest, mv) = crate::parser::mv::parse_move(text, text).unwrap();
+            mv.construct(&mut ctx).unwrap().mv
+        });
+        let axis = axis_mv.as_axis().unwrap().on(rink_mv.as_rink().unwrap());
+        let SpatialTransition::Absolute { pos, dir: got_dir } = axis.transition().spatial else {
+            panic!("no absolute transition for '{input}'");
+        };
+        assert_eq!((pos.x.0, pos.y.0), (x, y), "for '{rink}' '{input}'");
+        assert_eq!(got_dir.0, dir, "for '{rink}' '{input}'");
+    }
+}
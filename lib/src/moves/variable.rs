@@ -0,0 +1,105 @@
+// Copyright 2025 David Drysdale
+
+//! Pseudo-move definition for setting variables.
+
+use crate::{
+    moves::{self, MoveId, PseudoMoveId},
+    param, params,
+    params::Value,
+    parser, Bounds, Document, Move, MoveParam, ParseError, RenderOptions, Skater, SvgId,
+    TextPosition,
+};
+use std::borrow::Cow;
+
+#[derive(Debug, Clone)]
+pub struct Let {
+    text_pos: TextPosition,
+    pub name: String,
+    pub value: i32,
+}
+
+impl Let {
+    pub const MOVE: &'static str = "Let";
+    /// Static move information.
+    pub const INFO: moves::Info = moves::Info {
+        name: Self::MOVE,
+        id: MoveId::Pseudo(PseudoMoveId::Let),
+        summary: "Set a variable, for use as $name in later parameter values",
+        example: "Let[name=\"L\",value=600]",
+        visible: false,
+        params: &[
+            params::Info {
+                name: "name",
+                doc: "Name of the variable, which must start with a letter",
+                default: Value::Text(Cow::Borrowed("")),
+                range: params::Range::Text,
+                short: None,
+            },
+            params::Info {
+                name: "value",
+                doc: "Value of the variable",
+                default: Value::Number(0),
+                range: params::Range::Any,
+                short: None,
+            },
+        ],
+    };
+
+    pub fn from_params(text_pos: TextPosition, params: Vec<MoveParam>) -> Result<Self, ParseError> {
+        assert!(params::compatible(Self::INFO.params, &params));
+        let name = params[0].value.as_str(text_pos)?;
+        if !matches!(parser::params::parse_var_name(name), Ok(("", _))) {
+            return Err(ParseError {
+                pos: text_pos,
+                msg: format!("Invalid variable name '{name}'"),
+                file: None,
+                calls: Vec::new(),
+            });
+        }
+        Ok(Self {
+            text_pos,
+            name: name.to_string(),
+            value: params[1].value.as_i32(text_pos)?,
+        })
+    }
+}
+
+impl Move for Let {
+    fn id(&self) -> MoveId {
+        MoveId::Pseudo(PseudoMoveId::Let)
+    }
+    fn params(&self) -> Vec<MoveParam> {
+        vec![param!(self.name), param!(self.value)]
+    }
+    fn text(&self) -> String {
+        let params = params::to_string(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn expanded_text(&self) -> String {
+        let params = params::to_expanded(Self::INFO.params, &self.params());
+        format!("{}{params}", Self::INFO.name)
+    }
+    fn text_pos(&self) -> Option<TextPosition> {
+        Some(self.text_pos)
+    }
+    fn bounds(&self, _before: &Skater) -> Option<Bounds> {
+        None
+    }
+    fn render(
+        &self,
+        doc: Document,
+        _start: &Skater,
+        _opts: &mut RenderOptions,
+        _ns: Option<&SvgId>,
+    ) -> Document {
+        doc
+    }
+    fn opposite(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        self.box_clone(repeat)
+    }
+    fn box_clone(&self, repeat: Option<usize>) -> Box<dyn Move> {
+        let mut copy = self.clone();
+        copy.text_pos = self.text_pos.at_repeat(repeat);
+        Box::new(copy)
+    }
+}
@@ -6,6 +6,7 @@ use crate::{Centimetres, FontSize, ParseError, Percentage, Rotation, StrokeWidth
 use log::{error, trace};
 use serde::Serialize;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 
 /// Populate a [`MoveParam`].
@@ -52,6 +53,115 @@ pub enum Value {
     Text(Cow<'static, str>),
     /// Boolean value.
     Boolean(bool),
+    /// Arithmetic expression that has not yet been evaluated.
+    Expression(Expr),
+}
+
+/// Arithmetic operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Op {
+    /// Addition.
+    Add,
+    /// Subtraction.
+    Sub,
+    /// Multiplication.
+    Mul,
+    /// Integer division.
+    Div,
+}
+
+impl Op {
+    fn precedence(&self) -> u32 {
+        match self {
+            Op::Add | Op::Sub => 1,
+            Op::Mul | Op::Div => 2,
+        }
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Add => write!(f, "+"),
+            Op::Sub => write!(f, "-"),
+            Op::Mul => write!(f, "*"),
+            Op::Div => write!(f, "/"),
+        }
+    }
+}
+
+/// Integer arithmetic expression, which may refer to variables.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum Expr {
+    /// Literal number.
+    Number(i32),
+    /// Variable reference, written as `$name`.
+    Variable(String),
+    /// Negated expression.
+    Negate(Box<Expr>),
+    /// Binary operation.
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+impl Expr {
+    fn precedence(&self) -> u32 {
+        match self {
+            Expr::Binary(_, op, _) => op.precedence(),
+            Expr::Negate(_) => 3,
+            Expr::Number(_) | Expr::Variable(_) => 4,
+        }
+    }
+
+    /// Evaluate the expression, using the given values for variables.
+    pub fn eval(&self, vars: &HashMap<String, i32>) -> Result<i32, String> {
+        match self {
+            Expr::Number(v) => Ok(*v),
+            Expr::Variable(name) => vars
+                .get(name)
+                .copied()
+                .ok_or_else(|| format!("Unknown variable ${name}")),
+            Expr::Negate(e) => e
+                .eval(vars)?
+                .checked_neg()
+                .ok_or_else(|| format!("Overflow evaluating {self}")),
+            Expr::Binary(l, op, r) => {
+                let (l, r) = (l.eval(vars)?, r.eval(vars)?);
+                match op {
+                    Op::Add => l.checked_add(r),
+                    Op::Sub => l.checked_sub(r),
+                    Op::Mul => l.checked_mul(r),
+                    Op::Div if r == 0 => return Err(format!("Division by zero evaluating {self}")),
+                    Op::Div => l.checked_div(r),
+                }
+                .ok_or_else(|| format!("Overflow evaluating {self}"))
+            }
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(v) => write!(f, "{v}"),
+            Expr::Variable(name) => write!(f, "${name}"),
+            Expr::Negate(e) if e.precedence() < self.precedence() => write!(f, "-({e})"),
+            Expr::Negate(e) => write!(f, "-{e}"),
+            Expr::Binary(l, op, r) => {
+                // Operators are left-associative, so the right operand needs brackets for equal precedence.
+                if l.precedence() < op.precedence() {
+                    write!(f, "({l})")?;
+                } else {
+                    write!(f, "{l}")?;
+                }
+                write!(f, "{op}")?;
+                if r.precedence() <= op.precedence() {
+                    write!(f, "({r})")
+                } else {
+                    write!(f, "{r}")
+                }
+            }
+        }
+    }
 }
 
 impl Value {
@@ -77,6 +187,8 @@ impl Value {
         Ok(FontSize(val.try_into().map_err(|_e| ParseError {
             pos,
             msg: format!("Found negative font size {val}"),
+            file: None,
+            calls: Vec::new(),
         })?))
     }
     /// Extract the numeric value.
@@ -86,10 +198,20 @@ impl Value {
             Value::Boolean(v) => Err(ParseError {
                 pos,
                 msg: format!("Found boolean value '{v}', expected number"),
+                file: None,
+                calls: Vec::new(),
             }),
             Value::Text(v) => Err(ParseError {
                 pos,
                 msg: format!("Found text value '{v}', expected number"),
+                file: None,
+                calls: Vec::new(),
+            }),
+            Value::Expression(e) => Err(ParseError {
+                pos,
+                msg: format!("Found unevaluated expression '{e}', expected number"),
+                file: None,
+                calls: Vec::new(),
             }),
         }
     }
@@ -99,12 +221,22 @@ impl Value {
             Value::Number(v) => Err(ParseError {
                 pos,
                 msg: format!("Found number value '{v}', expected text"),
+                file: None,
+                calls: Vec::new(),
             }),
             Value::Boolean(v) => Err(ParseError {
                 pos,
                 msg: format!("Found boolean value '{v}', expected text"),
+                file: None,
+                calls: Vec::new(),
             }),
             Value::Text(v) => Ok(v),
+            Value::Expression(e) => Err(ParseError {
+                pos,
+                msg: format!("Found expression '{e}', expected text"),
+                file: None,
+                calls: Vec::new(),
+            }),
         }
     }
     /// Extract the boolean value.
@@ -113,11 +245,21 @@ impl Value {
             Value::Number(v) => Err(ParseError {
                 pos,
                 msg: format!("Found number value '{v}', expected boolean"),
+                file: None,
+                calls: Vec::new(),
             }),
             Value::Boolean(v) => Ok(*v),
             Value::Text(v) => Err(ParseError {
                 pos,
                 msg: format!("Found text value '{v}', expected boolean"),
+                file: None,
+                calls: Vec::new(),
+            }),
+            Value::Expression(e) => Err(ParseError {
+                pos,
+                msg: format!("Found expression '{e}', expected boolean"),
+                file: None,
+                calls: Vec::new(),
             }),
         }
     }
@@ -154,6 +296,7 @@ impl Display for Value {
             Value::Boolean(false) => write!(f, "false",),
             Value::Boolean(true) => write!(f, "true",),
             Value::Text(v) => write!(f, "\"{v}\"",),
+            Value::Expression(e) => write!(f, "{e}"),
         }
     }
 }
@@ -258,6 +401,8 @@ pub enum Range {
     StrictlyPositive,
     /// Only boolean values are allowed (resulting in `Value::Boolean`).
     Boolean,
+    /// Only text strings from a fixed set of choices are allowed (resulting in `Value::Text`).
+    Choice(&'static [&'static str]),
 }
 
 impl Display for Range {
@@ -268,6 +413,7 @@ impl Display for Range {
             Range::Positive => write!(f, "positive number"),
             Range::StrictlyPositive => write!(f, "non-zero positive number"),
             Range::Boolean => write!(f, "boolean"),
+            Range::Choice(choices) => write!(f, "one of \"{}\"", choices.join("\", \"")),
         }
     }
 }
@@ -277,7 +423,9 @@ impl Range {
     pub fn valid(&self, pos: TextPosition, val: &Value) -> Result<(), ParseError> {
         match (val, self) {
             (Value::Number(_v), Range::Any) => Ok(()),
-            (Value::Number(v), Range::Text) => Err(format!("{v} unexpected, want \"string\"")),
+            (Value::Number(v), Range::Text | Range::Choice(_)) => {
+                Err(format!("{v} unexpected, want \"string\""))
+            }
             (Value::Number(v), Range::Positive) if *v >= 0 => Ok(()),
             (Value::Number(v), Range::Positive) => Err(format!("{v} out of range, must be >= 0")),
             (Value::Number(v), Range::StrictlyPositive) if *v > 0 => Ok(()),
@@ -287,12 +435,20 @@ impl Range {
             (Value::Number(v), Range::Boolean) => Err(format!("{v} out of range, expect boolean")),
 
             (Value::Text(_v), Range::Text) => Ok(()),
+            (Value::Text(v), Range::Choice(choices)) if choices.contains(&v.as_ref()) => Ok(()),
             (Value::Text(v), range) => Err(format!("'{v}' unexpected, want {range}")),
 
             (Value::Boolean(_v), Range::Boolean) => Ok(()),
             (Value::Boolean(v), range) => Err(format!("'{v}' unexpected, want {range}")),
+
+            (Value::Expression(e), range) => Err(format!("'{e}' not evaluated, want {range}")),
         }
-        .map_err(|e| ParseError { pos, msg: e })
+        .map_err(|e| ParseError {
+            pos,
+            msg: e,
+            file: None,
+            calls: Vec::new(),
+        })
     }
 }
 
@@ -358,10 +514,10 @@ pub fn to_string(params_info: &[Info], params: &[MoveParam]) -> String {
         if param.value == info.default {
             // A default value can be assumed.
             done[idx] = true;
-        } else if let Some(abbrev) = info.short {
+        } else if let (Some(abbrev), Value::Number(value)) = (info.short, &param.value) {
+            let value = *value;
             let detents = abbrev.detents();
             let (u, d) = abbrev.chars();
-            let value = param.value.as_i32(Default::default()).unwrap();
             let short = if value == detents.add1 {
                 format!("{u}")
             } else if value == detents.add2 {
@@ -440,23 +596,36 @@ pub fn populate(
         crate::parser::params::parse(input).map_err(|_e| ParseError {
             pos,
             msg: format!("Failed to parse parameters in '{input}'"),
+            file: None,
+            calls: Vec::new(),
         })?;
     if !rest.is_empty() {
         return Err(ParseError {
             pos,
             msg: format!("Excess text '{rest}' left after parsing parameters"),
+            file: None,
+            calls: Vec::new(),
         });
     }
-    populate_from(params_info, pos, plus_minus, more_less, vals)
+    populate_from(
+        params_info,
+        pos,
+        plus_minus,
+        more_less,
+        vals,
+        &HashMap::new(),
+    )
 }
 
-/// Populate a collection of [`MoveParam`]s that match `params_info` from the given parsed values.
+/// Populate a collection of [`MoveParam`]s that match `params_info` from the given parsed values, evaluating any
+/// expressions using the values in `vars`.
 pub fn populate_from(
     params_info: &[Info],
     pos: TextPosition,
     plus_minus: Option<DetentLevel>,
     more_less: Option<DetentLevel>,
     vals: Vec<MoveParamRef>,
+    vars: &HashMap<String, i32>,
 ) -> Result<Vec<MoveParam>, ParseError> {
     // Begin with default values.
     // Invariant: entries in `params_info` and `params` are in sync.
@@ -483,6 +652,8 @@ pub fn populate_from(
             .ok_or_else(|| ParseError {
                 pos,
                 msg: "Found + or - but short code not supported".to_string(),
+                file: None,
+                calls: Vec::new(),
             })?;
         params[idx].value = detents.value(plus_minus);
     }
@@ -500,6 +671,8 @@ pub fn populate_from(
             .ok_or_else(|| ParseError {
                 pos,
                 msg: "Found < or > but short code not supported".to_string(),
+                file: None,
+                calls: Vec::new(),
             })?;
         params[idx].value = detents.value(more_less);
     }
@@ -514,12 +687,23 @@ pub fn populate_from(
                 None
             }
         }) {
-            params_info[idx].range.valid(pos, &val.value)?;
-            params[idx].value = val.value;
+            let value = match val.value {
+                Value::Expression(e) => Value::Number(e.eval(vars).map_err(|msg| ParseError {
+                    pos,
+                    msg,
+                    file: None,
+                    calls: Vec::new(),
+                })?),
+                value => value,
+            };
+            params_info[idx].range.valid(pos, &value)?;
+            params[idx].value = value;
         } else {
             return Err(ParseError {
                 pos,
                 msg: format!("Parameter {} not supported for this move", val.name),
+                file: None,
+                calls: Vec::new(),
             });
         }
     }
@@ -687,5 +871,41 @@ mod tests {
         assert!(populate(TEST_PARAMS_INFO, " [len1=-1]", Default::default()).is_err());
         assert!(populate(TEST_PARAMS_INFO, " [len1=0]", Default::default()).is_err());
         assert!(populate(TEST_PARAMS_INFO, " ++>>--", Default::default()).is_err());
+        assert!(populate(TEST_PARAMS_INFO, " [len2=$x]", Default::default()).is_err());
+    }
+
+    #[test]
+    fn test_populate_exprs() {
+        let vars = HashMap::from([("a".to_string(), 10), ("big".to_string(), i32::MAX)]);
+        let tests = [
+            ("[len2=$a]", Ok(10)),
+            ("[len2=$a*3/2]", Ok(15)),
+            ("[len2=100-$a*2]", Ok(80)),
+            ("[len2=(100-$a)*2]", Ok(180)),
+            ("[len2=-$a+20]", Ok(10)),
+            ("[len2=7/2]", Ok(3)),
+            ("[len2=$a-20]", Err("out of range")),
+            ("[len2=$b]", Err("Unknown variable $b")),
+            ("[len2=$a/0]", Err("Division by zero")),
+            ("[len2=$big+1]", Err("Overflow")),
+            ("[boolean=$a]", Err("expect boolean")),
+        ];
+        for (input, want) in tests {
+            let (_rest, (plus_minus, more_less, vals)) =
+                crate::parser::params::parse(input).unwrap();
+            let got = populate_from(
+                TEST_PARAMS_INFO,
+                Default::default(),
+                plus_minus,
+                more_less,
+                vals,
+                &vars,
+            );
+            match (got, want) {
+                (Ok(params), Ok(want)) => assert_eq!(params[1].value, Value::Number(want)),
+                (Err(e), Err(want)) => assert!(e.msg.contains(want), "for '{input}': {}", e.msg),
+                (got, want) => panic!("for '{input}': got {got:?}, want {want:?}"),
+            }
+        }
     }
 }
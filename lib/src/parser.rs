@@ -8,7 +8,7 @@ use nom::{
     bytes::complete::tag,
     character::complete::{multispace0, one_of, space0},
     combinator::{map, map_res, opt, recognize},
-    multi::{many1, separated_list0},
+    multi::many1,
     sequence::tuple,
     IResult, Parser,
 };
@@ -55,32 +55,53 @@ fn parse_separator(input: &str) -> IResult<&str, Vec<&str>> {
     )))(input)
 }
 
-pub(crate) fn parse(start: &str) -> IResult<&str, Vec<mv::TimedInputs<'_>>> {
+/// Parse the input into moves, recovering from any unparseable text by skipping to the next move separator.  Returns
+/// the moves that parsed successfully, together with an error for each chunk of unparseable text.
+pub(crate) fn parse(start: &str) -> (Vec<mv::TimedInputs<'_>>, Vec<ParseError>) {
+    let mut moves = Vec::new();
+    let mut errs = Vec::new();
     // Allow separators before the move text
-    let (rest, (_, moves)) = tuple((
-        opt(parse_separator),
-        separated_list0(parse_separator, |input| mv::parse_move(start, input)),
-    ))(start)?;
-    // The `separated_list0` combinator will leave a final separator in place if the thing after it doesn't parse as a
-    // move, so consume that (and any leading whitespace) too.
-    let rest = rest.trim_start();
-    let result = parse_separator(rest);
-    if let Ok((rest, _)) = result {
-        Ok((rest, moves))
-    } else {
-        Ok((rest, moves))
-    }
-}
+    let mut rest = match parse_separator(start) {
+        Ok((rest, _)) => rest,
+        Err(_) => start,
+    };
+    loop {
+        let bad = match mv::parse_move(start, rest) {
+            Ok((after, mv)) => {
+                moves.push(mv);
+                if let Ok((after, _)) = parse_separator(after) {
+                    rest = after;
+                    continue;
+                }
+                // Accept trailing whitespace without a separator at the end of the input.
+                if after.trim_start().is_empty() {
+                    break;
+                }
+                after
+            }
+            Err(_) if rest.trim_start().is_empty() => break,
+            Err(_) => rest,
+        };
 
-/// Convert a nom error into a [`ParseError`].
-pub(crate) fn err(err: nom::Err<nom::error::Error<&str>>, input: &str) -> ParseError {
-    ParseError {
-        pos: match &err {
-            nom::Err::Incomplete(_) => TextPosition::default(),
-            nom::Err::Error(e) | nom::Err::Failure(e) => TextPosition::new(input, e.input, e.input),
-        },
-        msg: format!("{err:?}"),
+        // Skip to the next thing that could start a separator, and resume from there.
+        let resync = bad
+            .find([';', '\n', '\r', '#'])
+            .map(|idx| &bad[idx..])
+            .unwrap_or(&bad[bad.len()..]);
+        let pos = TextPosition::new(start, bad, resync);
+        log::error!("failed to parse at {pos:?}: '{}'", &bad[..pos.count]);
+        errs.push(ParseError {
+            pos,
+            msg: "failed to parse".to_string(),
+            file: None,
+            calls: Vec::new(),
+        });
+        rest = match parse_separator(resync) {
+            Ok((after, _)) => after,
+            Err(_) => break,
+        };
     }
+    (moves, errs)
 }
 
 /// Parser wrapper to help in debugging, for when the output implements `Debug`.
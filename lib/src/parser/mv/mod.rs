@@ -4,11 +4,11 @@
 
 use crate::{
     moves::{self, repeat::RepeatEnd, PseudoMoveId, SkatingMoveId},
-    params::{self, DetentLevel, MoveParamRef},
+    params::{self, DetentLevel, MoveParamRef, Value},
     parser::timing::{parse_count, parse_duration},
     parser::{self, parse_i32, InnErr},
-    Code, Count, Duration, JumpCount, Move, MoveParam, ParseError, PreTransition, TextPosition,
-    TimedMove,
+    Code, Count, Duration, JumpCombo, JumpCount, JumpKind, Move, MoveParam, ParseError,
+    PreTransition, SpinKind, SpinPosition, TextPosition, TimedMove, MAX_COMBO_JUMPS,
 };
 use log::info;
 use nom::{
@@ -16,6 +16,7 @@ use nom::{
     bytes::complete::tag,
     character::complete::space0,
     combinator::{map, map_res, opt, value},
+    multi::many_m_n,
     sequence::{preceded, tuple},
     IResult, Parser,
 };
@@ -46,6 +47,30 @@ pub(crate) struct Inputs<'a> {
 }
 
 impl Inputs<'_> {
+    /// Return the static information for the move described by the inputs.
+    fn move_info(&self) -> &'static moves::Info {
+        match &self.info {
+            Info::Skating { move_id, .. } => move_id.info(),
+            Info::Pseudo { move_id } => move_id.info(),
+        }
+    }
+
+    /// Return any parameters that are specified as expressions.
+    fn exprs(&self) -> Vec<MoveParam> {
+        let info = self.move_info();
+        self.vals
+            .iter()
+            .filter(|val| matches!(val.value, Value::Expression(_)))
+            .filter_map(|val| {
+                let param = info.params.iter().find(|param| param.name == val.name)?;
+                Some(MoveParam {
+                    name: param.name,
+                    value: val.value.clone(),
+                })
+            })
+            .collect()
+    }
+
     /// Create the [`Move`] described by the inputs.
     pub fn construct(self, ctx: &mut moves::Context) -> Result<Box<dyn Move>, ParseError> {
         match self.info {
@@ -60,6 +85,7 @@ impl Inputs<'_> {
                     self.plus_minus,
                     self.more_less,
                     self.vals,
+                    &ctx.vars,
                 )?;
                 move_id.construct(self.input, self.text_pos, pre_transition, code, params, ctx)
             }
@@ -70,6 +96,7 @@ impl Inputs<'_> {
                     self.plus_minus,
                     self.more_less,
                     self.vals,
+                    &ctx.vars,
                 )?;
                 move_id.construct(self.text_pos, params, ctx)
             }
@@ -87,10 +114,14 @@ pub(crate) struct TimedInputs<'a> {
 impl TimedInputs<'_> {
     /// Create the [`TimedMove`] described by the inputs.
     pub fn construct(self, ctx: &mut moves::Context) -> Result<TimedMove, ParseError> {
+        let exprs = self.mv_inputs.exprs();
         Ok(TimedMove {
             count: self.count,
             duration: self.duration,
             mv: self.mv_inputs.construct(ctx)?,
+            exprs,
+            file: None,
+            calls: Vec::new(),
         })
     }
 }
@@ -115,29 +146,92 @@ fn parse_twizzle_id(input: &str) -> IResult<&str, SkatingMoveId> {
     .parse(input)
 }
 
-fn parse_jump_id(input: &str) -> IResult<&str, SkatingMoveId> {
-    // '-' [1234] {S,T,Lo,F,Lz,A}
-    let (rest, _) = tag("-")(input)?;
+fn parse_jump(input: &str) -> IResult<&str, (JumpKind, JumpCount)> {
+    // [1234] {S,T,Lo,F,Lz,A}
     let (rest, count) = alt((
         value(JumpCount::Single, tag("1")),
         value(JumpCount::Double, tag("2")),
         value(JumpCount::Triple, tag("3")),
         value(JumpCount::Quad, tag("4")),
+    ))(input)?;
+    let (rest, kind) = alt((
+        value(JumpKind::Salchow, tag(moves::jump::Salchow::JUMP)),
+        value(JumpKind::ToeLoop, tag(moves::jump::ToeLoop::JUMP)),
+        value(JumpKind::Loop, tag(moves::jump::Loop::JUMP)),
+        value(JumpKind::Flip, tag(moves::jump::Flip::JUMP)),
+        value(JumpKind::Lutz, tag(moves::jump::Lutz::JUMP)),
+        value(JumpKind::Axel, tag(moves::jump::Axel::JUMP)),
+        value(JumpKind::Euler, tag(moves::jump::Euler::JUMP)),
     ))(rest)?;
+    Ok((rest, (kind, count)))
+}
+
+fn parse_jump_id(input: &str) -> IResult<&str, SkatingMoveId> {
+    // '-' <jump> ('+' <jump>)* ['+SEQ']
+    let (rest, _) = tag("-")(input)?;
+    let (rest, first) = parse_jump(rest)?;
+    let (rest, more) = many_m_n(0, MAX_COMBO_JUMPS - 1, preceded(tag("+"), parse_jump))(rest)?;
+    if more.is_empty() {
+        return Ok((rest, SkatingMoveId::jump(first.0, first.1)));
+    }
+    let (rest, sequence) = map(
+        opt(tuple((tag("+"), tag(moves::jump::Combo::SEQUENCE)))),
+        |s| s.is_some(),
+    )(rest)?;
+    let mut jumps = vec![first];
+    jumps.extend(more);
+    let combo = JumpCombo::new(&jumps, sequence).expect("jump count in range");
+    Ok((rest, SkatingMoveId::JumpCombo(combo)))
+}
+
+fn parse_minor_jump_id(input: &str) -> IResult<&str, SkatingMoveId> {
+    // '-' {Wz,HalfF,HalfLz,Split,Stag,Walley,TWalley,Bunny}
     alt((
-        value(
-            SkatingMoveId::Salchow(count),
-            tag(moves::jump::Salchow::JUMP),
+        value(SkatingMoveId::WaltzJump, tag(moves::jump::WaltzJump::MOVE)),
+        value(SkatingMoveId::HalfFlip, tag(moves::jump::HalfFlip::MOVE)),
+        value(SkatingMoveId::HalfLutz, tag(moves::jump::HalfLutz::MOVE)),
+        value(SkatingMoveId::SplitJump, tag(moves::jump::SplitJump::MOVE)),
+        value(SkatingMoveId::StagJump, tag(moves::jump::StagJump::MOVE)),
+        value(SkatingMoveId::Walley, tag(moves::jump::Walley::MOVE)),
+        value(SkatingMoveId::ToeWalley, tag(moves::jump::ToeWalley::MOVE)),
+        value(SkatingMoveId::BunnyHop, tag(moves::jump::BunnyHop::MOVE)),
+    ))(input)
+}
+
+fn parse_spin_position(input: &str) -> IResult<&str, SpinPosition> {
+    // "Co" has to come before "C".
+    alt((
+        value(SpinPosition::Combination, tag("Co")),
+        value(SpinPosition::Upright, tag("U")),
+        value(SpinPosition::Sit, tag("S")),
+        value(SpinPosition::Camel, tag("C")),
+        value(SpinPosition::Layback, tag("L")),
+    ))(input)
+}
+
+fn parse_spin_id(input: &str) -> IResult<&str, SkatingMoveId> {
+    // '-' [F] [C] {U,S,C,L,Co} 'Sp'
+    let (rest, _) = tag("-")(input)?;
+    let (rest, flying) = map(opt(tag("F")), |f| f.is_some())(rest)?;
+    // A leading "C" is ambiguous between change-foot and camel, so try change-foot first.
+    let (rest, (change_foot, position)) = alt((
+        map(
+            tuple((tag("C"), parse_spin_position, tag(moves::spin::Spin::MOVE))),
+            |(_, position, _)| (true, position),
         ),
-        value(
-            SkatingMoveId::ToeLoop(count),
-            tag(moves::jump::ToeLoop::JUMP),
+        map(
+            tuple((parse_spin_position, tag(moves::spin::Spin::MOVE))),
+            |(position, _)| (false, position),
         ),
-        value(SkatingMoveId::LoopJump(count), tag(moves::jump::Loop::JUMP)),
-        value(SkatingMoveId::Flip(count), tag(moves::jump::Flip::JUMP)),
-        value(SkatingMoveId::Lutz(count), tag(moves::jump::Lutz::JUMP)),
-        value(SkatingMoveId::Axel(count), tag(moves::jump::Axel::JUMP)),
-    ))(rest)
+    ))(rest)?;
+    Ok((
+        rest,
+        SkatingMoveId::Spin(SpinKind {
+            position,
+            flying,
+            change_foot,
+        }),
+    ))
 }
 
 fn parse_skating_move_id(code: crate::Code, input: &str) -> IResult<&str, SkatingMoveId> {
@@ -181,8 +275,11 @@ fn parse_skating_move_id(code: crate::Code, input: &str) -> IResult<&str, Skatin
                 SkatingMoveId::ChangeOfEdge,
                 tag(moves::coe::ChangeOfEdge::MOVE_ALT),
             ),
+            parse_minor_jump_id,
             parse_twizzle_id,
             parse_jump_id,
+            parse_spin_id,
+            value(SkatingMoveId::Figure, tag(moves::figure::Figure::MOVE)),
             value(SkatingMoveId::Loop, tag(moves::loopfig::Loop::MOVE)),
             value(SkatingMoveId::Hop, tag(moves::loopfig::Loop::MOVE)),
             // Match an empty string for a plain edge last.
@@ -247,10 +344,17 @@ fn parse_pseudo_move_id(input: &str) -> IResult<&str, PseudoMoveId> {
         value(PseudoMoveId::Warp, tag(moves::warp::Warp::MOVE)),
         value(PseudoMoveId::Shift, tag(moves::shift::Shift::MOVE)),
         value(PseudoMoveId::Rink, tag(moves::rink::Rink::MOVE)),
+        value(PseudoMoveId::Axis, tag(moves::axis::Axis::MOVE)),
         value(PseudoMoveId::Info, tag(moves::info::Info::MOVE)),
         value(PseudoMoveId::Title, tag(moves::title::Title::MOVE)),
         value(PseudoMoveId::Text, tag(moves::text::Text::MOVE)),
         value(PseudoMoveId::Label, tag(moves::label::Label::MOVE)),
+        value(PseudoMoveId::Skater, tag(moves::skater::Skater::MOVE)),
+        value(PseudoMoveId::Partner, tag(moves::partner::Partner::MOVE)),
+        value(
+            PseudoMoveId::Formation,
+            tag(moves::formation::Formation::MOVE),
+        ),
         value(
             PseudoMoveId::RepeatStart,
             tag(moves::repeat::RepeatStart::MOVE),
@@ -259,6 +363,11 @@ fn parse_pseudo_move_id(input: &str) -> IResult<&str, PseudoMoveId> {
             PseudoMoveId::RepeatStart,
             tag(moves::repeat::RepeatStart::ALT_MOVE),
         ),
+        value(PseudoMoveId::Define, tag(moves::define::Define::MOVE)),
+        value(PseudoMoveId::DefineEnd, tag(moves::define::DefineEnd::MOVE)),
+        value(PseudoMoveId::Call, tag(moves::define::Call::MOVE)),
+        value(PseudoMoveId::Include, tag(moves::include::Include::MOVE)),
+        value(PseudoMoveId::Let, tag(moves::variable::Let::MOVE)),
     ))(input)
 }
 
@@ -40,7 +40,17 @@ fn test_valid_text() {
         "!|",
         "!| x 2",
         ":|x4",
+        "Define[name=\"prog\"]\nLFO;RFI\nEnd\nCall[name=\"prog\",opposite=true]",
+        "Include[file=\"waltz.skate\"]\nLFO",
         "LBI-2S",
+        "LBO-3Lz+3T",
+        "LBO-3Lz+3T+",
+        "LFO-3A+2A+SEQ",
+        "LFO-Wz",
+        "RBI-TWalley",
+        "LBO-3Lz+1Eu+3S",
+        "LFO-USp",
+        "RFO-FCCoSp+",
         "LFO",
         "LFO+",
         "LFO+>>",
@@ -57,12 +67,73 @@ fn test_valid_text() {
         "Warp [x=200,y=4400,dir=270] # extend bounds\nWarp [x=200,y=4200,dir=270]",
     ];
     for input in tests {
-        let result = crate::parser::parse(input);
-        assert!(result.is_ok(), "for input '{input}'");
-        let (rest, _moves) = result.unwrap();
-        assert!(
-            rest.is_empty(),
-            "got '{rest}' left over after parsing '{input}'"
-        );
+        let (_moves, errs) = crate::parser::parse(input);
+        assert!(errs.is_empty(), "got {errs:?} parsing '{input}'");
     }
 }
+
+#[test]
+fn test_spin_ids() {
+    let tests = [
+        ("-USp", SpinPosition::Upright, false, false),
+        ("-SSp", SpinPosition::Sit, false, false),
+        ("-CSp", SpinPosition::Camel, false, false),
+        ("-LSp", SpinPosition::Layback, false, false),
+        ("-CoSp", SpinPosition::Combination, false, false),
+        ("-FCSp", SpinPosition::Camel, true, false),
+        ("-CCSp", SpinPosition::Camel, false, true),
+        ("-CCoSp", SpinPosition::Combination, false, true),
+        ("-FCCoSp", SpinPosition::Combination, true, true),
+    ];
+    for (input, position, flying, change_foot) in tests {
+        let (rest, got) = parse_spin_id(input).unwrap();
+        assert!(rest.is_empty(), "for input '{input}'");
+        let want = SkatingMoveId::Spin(SpinKind {
+            position,
+            flying,
+            change_foot,
+        });
+        assert_eq!(got, want, "for input '{input}'");
+        let SkatingMoveId::Spin(kind) = got else {
+            unreachable!()
+        };
+        assert_eq!(format!("-{kind}"), input);
+    }
+}
+
+#[test]
+fn test_jump_ids() {
+    use JumpCount::*;
+    use JumpKind::*;
+    let tests = [
+        ("-2A", vec![(Axel, Double)], false),
+        ("-3Lz+3T", vec![(Lutz, Triple), (ToeLoop, Triple)], false),
+        (
+            "-3F+1Lo+3S",
+            vec![(Flip, Triple), (Loop, Single), (Salchow, Triple)],
+            false,
+        ),
+        ("-3A+2A+SEQ", vec![(Axel, Triple), (Axel, Double)], true),
+        ("-1Eu", vec![(Euler, Single)], false),
+        (
+            "-3F+1Eu+3S",
+            vec![(Flip, Triple), (Euler, Single), (Salchow, Triple)],
+            false,
+        ),
+    ];
+    for (input, jumps, sequence) in tests {
+        let (rest, got) = parse_jump_id(input).unwrap();
+        assert!(rest.is_empty(), "for input '{input}'");
+        let want = match JumpCombo::new(&jumps, sequence) {
+            Some(combo) => SkatingMoveId::JumpCombo(combo),
+            None => SkatingMoveId::jump(jumps[0].0, jumps[0].1),
+        };
+        assert_eq!(got, want, "for input '{input}'");
+        if let SkatingMoveId::JumpCombo(combo) = got {
+            assert_eq!(format!("-{combo}"), input);
+        }
+    }
+    // At most three jumps are consumed.
+    let (rest, _got) = parse_jump_id("-1T+1T+1T+1T").unwrap();
+    assert_eq!(rest, "+1T");
+}
@@ -2,7 +2,7 @@
 
 //! Parameter parsing.
 
-use crate::params::{DetentLevel, MoveParamRef, Value};
+use crate::params::{DetentLevel, Expr, MoveParamRef, Op, Value};
 use crate::parser::{self, parse_i32};
 use nom::{
     branch::alt,
@@ -29,12 +29,73 @@ fn parse_bool(input: &str) -> IResult<&str, bool> {
     ))(input)
 }
 
+/// Parse a variable name, without the leading `$`.
+pub fn parse_var_name(input: &str) -> IResult<&str, &str> {
+    recognize(pair(
+        // Starts with a letter
+        alpha1,
+        // Followed by zero or more letters, numbers or underscores (but not dashes, which would be ambiguous with
+        // subtraction).
+        many0(alt((alphanumeric1, tag("_")))),
+    ))
+    .parse(input)
+}
+
+/// Parse a number, variable, bracketed expression or negated term.
+fn parse_factor(input: &str) -> IResult<&str, Expr> {
+    alt((
+        map(parse_i32, Expr::Number),
+        map(preceded(char('$'), parse_var_name), |name| {
+            Expr::Variable(name.to_string())
+        }),
+        delimited(pair(char('('), space0), parse_expr, pair(space0, char(')'))),
+        map(preceded(char('-'), parse_factor), |e| {
+            Expr::Negate(Box::new(e))
+        }),
+    ))(input)
+}
+
+/// Parse a left-associative chain of operands separated by any of the given operators.
+fn parse_chain<'a>(
+    input: &'a str,
+    ops: [(char, Op); 2],
+    operand: fn(&'a str) -> IResult<&'a str, Expr>,
+) -> IResult<&'a str, Expr> {
+    let (rest, first) = operand(input)?;
+    let (rest, others) = many0(tuple((
+        space0,
+        alt((
+            value(ops[0].1, char(ops[0].0)),
+            value(ops[1].1, char(ops[1].0)),
+        )),
+        space0,
+        operand,
+    )))(rest)?;
+    let expr = others.into_iter().fold(first, |l, (_, op, _, r)| {
+        Expr::Binary(Box::new(l), op, Box::new(r))
+    });
+    Ok((rest, expr))
+}
+
+fn parse_term(input: &str) -> IResult<&str, Expr> {
+    parse_chain(input, [('*', Op::Mul), ('/', Op::Div)], parse_factor)
+}
+
+/// Parse an integer arithmetic expression.
+pub fn parse_expr(input: &str) -> IResult<&str, Expr> {
+    parse_chain(input, [('+', Op::Add), ('-', Op::Sub)], parse_term)
+}
+
 /// Parse a [`Value`] from text.
 pub fn parse_value(input: &str) -> IResult<&str, Value> {
     alt((
         |input| parser::string::parse(input).map(|(rest, v)| (rest, Value::Text(Cow::Owned(v)))),
         |input| parse_bool(input).map(|(rest, v)| (rest, Value::Boolean(v))),
-        |input| parse_i32(input).map(|(rest, v)| (rest, Value::Number(v))),
+        // A plain number is a literal, not an expression.
+        map(parse_expr, |e| match e {
+            Expr::Number(v) => Value::Number(v),
+            e => Value::Expression(e),
+        }),
     ))(input)
 }
 
@@ -48,6 +48,15 @@ fn test_parse_value_ok() {
         ("42xy", Value::Number(42), "xy"),
         ("-42xy", Value::Number(-42), "xy"),
         (r#""test"xy"#, Value::Text(Cow::Borrowed("test")), "xy"),
+        (
+            "$L+1,xy",
+            Value::Expression(Expr::Binary(
+                Box::new(Expr::Variable("L".to_string())),
+                Op::Add,
+                Box::new(Expr::Number(1)),
+            )),
+            ",xy",
+        ),
     ];
 
     for (input, want, want_rest) in tests {
@@ -58,6 +67,35 @@ fn test_parse_value_ok() {
     }
 }
 
+#[test]
+fn test_parse_expr() {
+    let tests = [
+        ("42,xy", "42", ",xy"),
+        ("$L,xy", "$L", ",xy"),
+        ("$L*3/2]", "$L*3/2", "]"),
+        ("$L * 3 / 2 ,xy", "$L*3/2", " ,xy"),
+        ("180-$a,xy", "180-$a", ",xy"),
+        ("180 - -$a", "180--$a", ""),
+        ("(1+2)*3", "(1+2)*3", ""),
+        ("1+(2*3)", "1+2*3", ""),
+        ("1-(2-3)", "1-(2-3)", ""),
+        ("( $a_1 + 2 )", "$a_1+2", ""),
+        ("-(1+2)", "-(1+2)", ""),
+        ("2*-3", "2*-3", ""),
+        ("1-", "1", "-"),
+        ("$a-b", "$a", "-b"),
+    ];
+    for (input, want, want_rest) in tests {
+        let (got_rest, got) =
+            parse_expr(input).unwrap_or_else(|e| panic!("parse failed for input: {input}, {e:?}"));
+        assert_eq!(got.to_string(), want, "for input: {input}");
+        assert_eq!(got_rest, want_rest, "for input: {input}");
+        // The displayed form should parse back to the same expression.
+        let (_, regot) = parse_expr(want).unwrap();
+        assert_eq!(regot, got, "for input: {input}");
+    }
+}
+
 #[test]
 fn test_parse_name() {
     let tests = [
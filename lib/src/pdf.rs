@@ -0,0 +1,192 @@
+// Copyright 2025 David Drysdale
+
+//! PDF output of diagrams.
+
+use crate::raster::{output_err, parse_svg};
+use crate::{OutputError, Resolver, Warning};
+use log::info;
+use regex::Regex;
+use svg::node::element::{Path, Text};
+use svg::node::Blob;
+use svg::Document;
+
+/// Page margin, in millimetres.
+const PAGE_MARGIN: f64 = 15.0;
+/// Height of the title block at the top of the page, in millimetres.
+const TITLE_HEIGHT: f64 = 20.0;
+/// Font size for the title, in millimetres.
+const TITLE_FONT_SIZE: f64 = 8.0;
+/// Font size for the scale information, in millimetres.
+const SCALE_FONT_SIZE: f64 = 3.5;
+/// Resolution used when converting SVG units to PDF points.
+const DPI: f32 = 96.0;
+
+/// Physical page size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PageSize {
+    /// ISO A4.
+    #[default]
+    A4,
+    /// US Letter.
+    Letter,
+}
+
+impl PageSize {
+    /// Dimensions of the page in portrait orientation, in millimetres.
+    fn portrait_mm(&self) -> (f64, f64) {
+        match self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+/// Scale at which to print a diagram.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PdfScale {
+    /// Scale the diagram to fit the page.
+    #[default]
+    Fit,
+    /// Print the diagram at a true scale of 1:N.
+    Ratio(u32),
+}
+
+/// Options for PDF output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PdfOptions {
+    /// Page size; the orientation of the page follows the shape of the diagram.
+    pub page: PageSize,
+    /// Scale for the diagram.
+    pub scale: PdfScale,
+}
+
+/// Generate PDF for the given input, as a single page with a title block, using `resolver` to find the contents of
+/// any included files.
+pub fn generate_pdf(
+    input: &str,
+    resolver: &dyn Resolver,
+    opts: PdfOptions,
+) -> Result<Vec<u8>, OutputError> {
+    generate_pdf_with_warnings(input, resolver, opts).map(|(pdf, _warnings)| pdf)
+}
+
+/// Generate PDF for the given input, also returning any warnings about questionable input.
+pub fn generate_pdf_with_warnings(
+    input: &str,
+    resolver: &dyn Resolver,
+    opts: PdfOptions,
+) -> Result<(Vec<u8>, Vec<Warning>), OutputError> {
+    let generated = crate::generate_internal(input, resolver, None, false)?;
+    let page = page_svg(&generated.svg, &generated.title, opts)?;
+    let tree = parse_svg(&page)?;
+    let pdf = svg2pdf::to_pdf(
+        &tree,
+        svg2pdf::ConversionOptions::default(),
+        svg2pdf::PageOptions { dpi: DPI },
+    )
+    .map_err(|e| output_err(format!("failed to convert to PDF: {e}")))?;
+    Ok((pdf, generated.warnings))
+}
+
+/// Build SVG for a page (in millimetre units) that holds the title block and the diagram.
+fn page_svg(diagram: &str, title: &str, opts: PdfOptions) -> Result<String, OutputError> {
+    // Extract the dimensions of the diagram, which are in centimetres, from the outermost element.
+    let start = diagram
+        .find("<svg")
+        .ok_or_else(|| output_err("no SVG element in diagram".to_string()))?;
+    let end = start
+        + diagram[start..]
+            .find('>')
+            .ok_or_else(|| output_err("unterminated SVG element in diagram".to_string()))?;
+    let tag = &diagram[start..end];
+    let dim_re = Regex::new(r#"\s(width|height)="([0-9.]+)""#).unwrap();
+    let (mut width, mut height) = (0.0, 0.0);
+    for caps in dim_re.captures_iter(tag) {
+        let value: f64 = caps[2].parse().unwrap_or_default();
+        match &caps[1] {
+            "width" => width = value,
+            _ => height = value,
+        }
+    }
+    if width <= 0.0 || height <= 0.0 {
+        return Err(output_err("diagram has no size".to_string()));
+    }
+
+    // Lay the page out to match the shape of the diagram.
+    let (short, long) = opts.page.portrait_mm();
+    let (page_width, page_height) = if width > height {
+        (long, short)
+    } else {
+        (short, long)
+    };
+    let avail_width = page_width - 2.0 * PAGE_MARGIN;
+    let avail_height = page_height - 2.0 * PAGE_MARGIN - TITLE_HEIGHT;
+
+    // Scale is in millimetres of paper per centimetre of ice.
+    let (scale, scale_text) = match opts.scale {
+        PdfScale::Fit => {
+            let scale = f64::min(avail_width / width, avail_height / height);
+            (scale, format!("Scale approx. 1:{}", (10.0 / scale).round()))
+        }
+        PdfScale::Ratio(0) => return Err(output_err("scale must be 1:N with N > 0".to_string())),
+        PdfScale::Ratio(n) => {
+            let scale = 10.0 / n as f64;
+            if width * scale > avail_width || height * scale > avail_height {
+                return Err(output_err(format!(
+                    "diagram needs {:.0}x{:.0}mm at 1:{n}, but the page only has {avail_width:.0}x{avail_height:.0}mm",
+                    width * scale,
+                    height * scale,
+                )));
+            }
+            (scale, format!("Scale 1:{n}"))
+        }
+    };
+    let (diagram_width, diagram_height) = (width * scale, height * scale);
+    let x = PAGE_MARGIN + (avail_width - diagram_width) / 2.0;
+    let y = PAGE_MARGIN + TITLE_HEIGHT;
+    info!("place {width}x{height}cm diagram at ({x:.1},{y:.1}) on {page_width}x{page_height}mm page, scale {scale}");
+
+    let placed = format!(
+        "{} x=\"{x:.2}\" y=\"{y:.2}\" width=\"{diagram_width:.2}\" height=\"{diagram_height:.2}\"{}",
+        dim_re.replace_all(tag, ""),
+        &diagram[end..]
+    );
+
+    let centre = page_width / 2.0;
+    let rule_y = PAGE_MARGIN + TITLE_HEIGHT - 4.0;
+    let doc = Document::new()
+        .set("xmlns:xlink", "http://www.w3.org/1999/xlink")
+        .set("width", format!("{page_width}mm"))
+        .set("height", format!("{page_height}mm"))
+        .set("viewBox", format!("0 0 {page_width} {page_height}"))
+        .add(
+            Text::new(title)
+                .set("x", centre)
+                .set("y", PAGE_MARGIN + TITLE_FONT_SIZE)
+                .set("text-anchor", "middle")
+                .set("font-size", TITLE_FONT_SIZE),
+        )
+        .add(
+            Text::new(scale_text)
+                .set("x", centre)
+                .set("y", PAGE_MARGIN + TITLE_FONT_SIZE + 2.0 * SCALE_FONT_SIZE)
+                .set("text-anchor", "middle")
+                .set("font-size", SCALE_FONT_SIZE),
+        )
+        .add(
+            Path::new()
+                .set(
+                    "d",
+                    format!(
+                        "M {PAGE_MARGIN},{rule_y} L {},{rule_y}",
+                        page_width - PAGE_MARGIN
+                    ),
+                )
+                .set("style", "stroke:black; stroke-width:0.3;"),
+        )
+        .add(Blob::new(placed));
+
+    let mut svg = Vec::new();
+    svg::write(&mut svg, &doc).map_err(|e| output_err(format!("failed to write page: {e}")))?;
+    String::from_utf8(svg).map_err(|e| output_err(format!("failed to write page: {e}")))
+}
@@ -0,0 +1,103 @@
+// Copyright 2025 David Drysdale
+
+//! Rasterization of generated diagrams.
+
+use crate::{OutputError, Resolver, Warning};
+use log::info;
+use regex::{Captures, Regex};
+use resvg::{tiny_skia, usvg};
+use std::borrow::Cow;
+
+/// Font used for all text, bundled so that output does not depend on the fonts installed locally.
+const FONT_DATA: &[u8] = include_bytes!("../../third_party/fonts/dejavu/DejaVuSans.ttf");
+const FONT_FAMILY: &str = "DejaVu Sans";
+
+/// Resolution that corresponds to one SVG user unit per pixel.
+const BASE_DPI: u32 = 96;
+
+/// Size of rasterized output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngSize {
+    /// Scale output to the given width in pixels, preserving aspect ratio.
+    Width(u32),
+    /// Scale output to the given resolution, where 96 DPI gives one pixel per centimetre of ice.
+    Dpi(u32),
+}
+
+impl Default for PngSize {
+    fn default() -> Self {
+        Self::Dpi(BASE_DPI)
+    }
+}
+
+/// Replace characters that are tolerated in element IDs by browsers, but not by the rasterizer.
+fn sanitize_ids(svg: &str) -> Cow<'_, str> {
+    let re = Regex::new(r#"(id|xlink:href)="(#?)([^"]* [^"]*)""#).unwrap();
+    re.replace_all(svg, |caps: &Captures| {
+        format!(
+            "{}=\"{}{}\"",
+            &caps[1],
+            &caps[2],
+            caps[3].replace(' ', "_x20_")
+        )
+    })
+}
+
+pub(crate) fn output_err(msg: String) -> OutputError {
+    OutputError::Render(msg)
+}
+
+/// Parse SVG into a tree that is ready for rendering, using the bundled font for all text.
+pub(crate) fn parse_svg(svg: &str) -> Result<usvg::Tree, OutputError> {
+    let mut opts = usvg::Options {
+        font_family: FONT_FAMILY.to_string(),
+        ..Default::default()
+    };
+    opts.fontdb_mut().load_font_data(FONT_DATA.to_vec());
+    usvg::Tree::from_str(&sanitize_ids(svg), &opts)
+        .map_err(|e| output_err(format!("failed to process SVG: {e}")))
+}
+
+/// Convert SVG (as emitted by [`crate::generate`]) into PNG data.
+pub fn svg_to_png(svg: &str, size: PngSize) -> Result<Vec<u8>, OutputError> {
+    let tree = parse_svg(svg)?;
+
+    let svg_size = tree.size();
+    let scale = match size {
+        PngSize::Width(width) => width as f32 / svg_size.width(),
+        PngSize::Dpi(dpi) => dpi as f32 / BASE_DPI as f32,
+    };
+    let width = (svg_size.width() * scale).round() as u32;
+    let height = (svg_size.height() * scale).round() as u32;
+    info!("rasterize {svg_size:?} to {width}x{height}");
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| output_err(format!("invalid image size {width}x{height}")))?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|e| output_err(format!("failed to encode PNG: {e}")))
+}
+
+/// Generate PNG for the given input, using `resolver` to find the contents of any included files.
+pub fn generate_png(
+    input: &str,
+    resolver: &dyn Resolver,
+    size: PngSize,
+) -> Result<Vec<u8>, OutputError> {
+    generate_png_with_warnings(input, resolver, size).map(|(png, _warnings)| png)
+}
+
+/// Generate PNG for the given input, also returning any warnings about questionable input.
+pub fn generate_png_with_warnings(
+    input: &str,
+    resolver: &dyn Resolver,
+    size: PngSize,
+) -> Result<(Vec<u8>, Vec<Warning>), OutputError> {
+    let (svg, warnings) = crate::generate_with_warnings(input, resolver)?;
+    Ok((svg_to_png(&svg, size)?, warnings))
+}
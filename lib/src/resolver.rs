@@ -0,0 +1,59 @@
+// Copyright 2025 David Drysdale
+
+//! Lookup of files referenced by `Include` pseudo-moves.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+/// Source of the contents of included files.
+pub trait Resolver {
+    /// Return the contents of the named file.
+    fn resolve(&self, file: &str) -> io::Result<String>;
+}
+
+/// Resolver that serves included files from memory.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryResolver {
+    files: HashMap<String, String>,
+}
+
+impl MemoryResolver {
+    /// Add (or replace) the contents of the named file.
+    pub fn insert(&mut self, file: &str, contents: &str) {
+        self.files.insert(file.to_string(), contents.to_string());
+    }
+
+    /// Remove all files.
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
+}
+
+impl Resolver for MemoryResolver {
+    fn resolve(&self, file: &str) -> io::Result<String> {
+        self.files
+            .get(file)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "file not found"))
+    }
+}
+
+/// Resolver that reads included files from disk, relative to a base directory.
+#[derive(Debug, Clone)]
+pub struct FileResolver {
+    dir: PathBuf,
+}
+
+impl FileResolver {
+    /// Create a resolver that looks for files relative to `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Resolver for FileResolver {
+    fn resolve(&self, file: &str) -> io::Result<String> {
+        std::fs::read_to_string(self.dir.join(file))
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright 2025 David Drysdale
+
+//! Well-balanced program rules for jump elements.
+
+use crate::{
+    moves::MoveId, moves::SkatingMoveId, JumpCount, JumpKind, TimedMove, Warning, WarningKind,
+};
+use std::collections::HashMap;
+
+/// Rule set for a competition segment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RuleSet {
+    /// Senior short program.
+    SeniorShort,
+    /// Senior free skating.
+    #[default]
+    SeniorFree,
+    /// Junior short program.
+    JuniorShort,
+    /// Junior free skating.
+    JuniorFree,
+}
+
+/// Limits on jump elements for a rule set.
+struct Limits {
+    /// Maximum number of jump elements.
+    jump_elements: usize,
+    /// Maximum number of jump combinations and sequences.
+    combinations: usize,
+    /// Whether jump sequences are allowed.
+    sequences: bool,
+    /// Maximum number of rotations for any jump.
+    rotations: JumpCount,
+    /// Maximum number of executions of the same triple or quad jump.
+    executions: usize,
+    /// Maximum number of distinct triple and quad jumps that can be repeated.
+    repeated_jumps: usize,
+    /// Maximum number of distinct quad jumps that can be repeated.
+    repeated_quads: usize,
+}
+
+impl RuleSet {
+    fn limits(&self) -> Limits {
+        match self {
+            RuleSet::SeniorShort | RuleSet::JuniorShort => Limits {
+                jump_elements: 3,
+                combinations: 1,
+                sequences: false,
+                rotations: if *self == RuleSet::JuniorShort {
+                    JumpCount::Triple
+                } else {
+                    JumpCount::Quad
+                },
+                executions: 1,
+                repeated_jumps: 0,
+                repeated_quads: 0,
+            },
+            RuleSet::SeniorFree | RuleSet::JuniorFree => Limits {
+                jump_elements: 7,
+                combinations: 3,
+                sequences: true,
+                rotations: JumpCount::Quad,
+                executions: 2,
+                repeated_jumps: 2,
+                repeated_quads: 1,
+            },
+        }
+    }
+}
+
+/// Record of the executions of a triple or quad jump.
+#[derive(Default)]
+struct Executions {
+    count: usize,
+    in_combination: bool,
+}
+
+/// Check the jump elements in (fully expanded) moves against a rule set.
+pub(crate) fn check_moves(moves: &[TimedMove], rules: RuleSet) -> Vec<Warning> {
+    let limits = rules.limits();
+    let mut warnings = Vec::new();
+    let mut elements = 0;
+    let mut combinations = 0;
+    let mut three_jump_combinations = 0;
+    let mut repeated_jumps = 0;
+    let mut repeated_quads = 0;
+    let mut has_axel = false;
+    let mut executions: HashMap<(JumpKind, JumpCount), Executions> = HashMap::new();
+
+    for timed_mv in moves {
+        let MoveId::Skating(id) = timed_mv.mv.id() else {
+            continue;
+        };
+        let (jumps, combo) = match id {
+            SkatingMoveId::JumpCombo(combo) => (combo.jumps().to_vec(), Some(combo)),
+            _ => match id.as_jump() {
+                Some(jump) => (vec![jump], None),
+                None => continue,
+            },
+        };
+        let pos = timed_mv.mv.text_pos().unwrap_or_default();
+        let mut warn = |kind| {
+            let (file, calls) = (timed_mv.file.clone(), timed_mv.calls.clone());
+            warnings.push(Warning {
+                pos,
+                kind,
+                file,
+                calls,
+            })
+        };
+
+        elements += 1;
+        if elements > limits.jump_elements {
+            warn(WarningKind::TooManyJumpElements {
+                max: limits.jump_elements,
+            });
+        }
+        if let Some(combo) = combo {
+            combinations += 1;
+            if combinations > limits.combinations {
+                warn(WarningKind::TooManyCombinations {
+                    max: limits.combinations,
+                });
+            }
+            if combo.sequence && !limits.sequences {
+                warn(WarningKind::SequenceNotAllowed);
+            }
+            if jumps.len() == 3 {
+                three_jump_combinations += 1;
+                if three_jump_combinations > 1 {
+                    warn(WarningKind::TooManyThreeJumpCombinations);
+                }
+            }
+        }
+
+        for (kind, count) in jumps {
+            if kind == JumpKind::Axel {
+                has_axel = true;
+            }
+            if count > limits.rotations {
+                warn(WarningKind::TooManyRotations {
+                    max: limits.rotations,
+                });
+            }
+            if count < JumpCount::Triple {
+                continue;
+            }
+            let record = executions.entry((kind, count)).or_default();
+            record.count += 1;
+            record.in_combination |= combo.is_some();
+            if record.count > limits.executions {
+                warn(WarningKind::JumpRepeated { kind, count });
+            } else if record.count == 2 {
+                repeated_jumps += 1;
+                if repeated_jumps > limits.repeated_jumps {
+                    warn(WarningKind::TooManyRepeatedJumps {
+                        max: limits.repeated_jumps,
+                    });
+                }
+                if count == JumpCount::Quad {
+                    repeated_quads += 1;
+                    if repeated_quads > limits.repeated_quads {
+                        warn(WarningKind::TooManyRepeatedQuads {
+                            max: limits.repeated_quads,
+                        });
+                    }
+                }
+                if !record.in_combination {
+                    warn(WarningKind::RepeatNotInCombination { kind, count });
+                }
+            }
+        }
+    }
+
+    if !has_axel {
+        warnings.push(Warning {
+            pos: Default::default(),
+            kind: WarningKind::MissingAxel,
+            file: None,
+            calls: Vec::new(),
+        });
+    }
+    warnings
+}
@@ -0,0 +1,194 @@
+// Copyright 2025 David Drysdale
+
+//! Element codes and base values for a program.
+
+use crate::{
+    moves::MoveId, moves::SkatingMoveId, steps, steps::Variety, ParseError, TextPosition, TimedMove,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Bundled scale of values.
+const BUNDLED_SOV: &str = include_str!("../data/sov.txt");
+
+/// Score, in hundredths of a point.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Points(pub u32);
+
+impl Display for Points {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+impl std::ops::Add<Points> for Points {
+    type Output = Self;
+    fn add(self, other: Points) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl std::iter::Sum for Points {
+    fn sum<I: Iterator<Item = Points>>(iter: I) -> Self {
+        iter.fold(Points::default(), |a, b| a + b)
+    }
+}
+
+/// Parse a value like "5.90" into [`Points`].
+fn parse_points(text: &str) -> Option<Points> {
+    let (whole, frac) = text.split_once('.').unwrap_or((text, ""));
+    if frac.len() > 2 || !frac.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let whole: u32 = whole.parse().ok()?;
+    let frac: u32 = format!("{frac:0<2}").parse().ok()?;
+    Some(Points(whole * 100 + frac))
+}
+
+/// Scale of values, mapping ISU element codes to base values.
+#[derive(Debug, Clone)]
+pub struct ScaleOfValues {
+    values: HashMap<String, Points>,
+}
+
+impl Default for ScaleOfValues {
+    /// Scale of values bundled with the library.
+    fn default() -> Self {
+        Self::parse(BUNDLED_SOV).expect("bundled scale of values is valid")
+    }
+}
+
+impl ScaleOfValues {
+    /// Parse a scale of values, in the format of the bundled `data/sov.txt` file: each line holds an element code and
+    /// a base value, and '#' starts a comment.
+    pub fn parse(data: &str) -> Result<Self, ParseError> {
+        let mut values = HashMap::new();
+        for (row, line) in data.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let value = match fields.as_slice() {
+                [] => continue,
+                [code, value] => parse_points(value).map(|value| (code.to_string(), value)),
+                _ => None,
+            };
+            let Some((code, value)) = value else {
+                return Err(ParseError {
+                    pos: TextPosition {
+                        row,
+                        col: 0,
+                        count: line.len(),
+                        repeat: None,
+                    },
+                    msg: format!("Invalid scale of values entry '{}'", line.trim()),
+                    file: None,
+                    calls: Vec::new(),
+                });
+            };
+            values.insert(code, value);
+        }
+        Ok(Self { values })
+    }
+
+    /// Return the base value for an element code.
+    pub fn base_value(&self, code: &str) -> Option<Points> {
+        self.values.get(code).copied()
+    }
+}
+
+/// Return the ISU element code for a skating move, or `None` if it is not a scored element.
+///
+/// Spins have no level information, so are given the base level "B".
+pub fn element_code(id: SkatingMoveId) -> Option<String> {
+    match id {
+        SkatingMoveId::JumpCombo(combo) => Some(combo.to_string()),
+        SkatingMoveId::Spin(kind) => Some(format!("{kind}B")),
+        _ => id.as_jump().map(|(kind, count)| format!("{count}{kind}")),
+    }
+}
+
+/// Scored element of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoredElement {
+    /// Position of the element in the input.
+    pub pos: TextPosition,
+    /// ISU element code.
+    pub code: String,
+    /// Base value of the element, or `None` if the scale of values has no entry for it.
+    pub base_value: Option<Points>,
+}
+
+/// Table of base values for the elements of a program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScoreSheet {
+    /// Scored elements, in program order.
+    pub elements: Vec<ScoredElement>,
+    /// Total base value of the elements that have one.
+    pub total: Points,
+}
+
+impl Display for ScoreSheet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<4} {:<16} {:>6}", "#", "Element", "Base")?;
+        for (idx, element) in self.elements.iter().enumerate() {
+            let base_value = match element.base_value {
+                Some(value) => value.to_string(),
+                None => "-".to_string(),
+            };
+            writeln!(f, "{:<4} {:<16} {:>6}", idx + 1, element.code, base_value)?;
+        }
+        writeln!(f, "{:<4} {:<16} {:>6}", "", "Total", self.total)
+    }
+}
+
+/// Calculate the base value of an element, where combinations and sequences are worth the sum of their jumps.
+fn base_value(id: SkatingMoveId, code: &str, sov: &ScaleOfValues) -> Option<Points> {
+    match id {
+        SkatingMoveId::JumpCombo(combo) => combo
+            .jumps()
+            .iter()
+            .map(|(kind, count)| sov.base_value(&format!("{count}{kind}")))
+            .sum(),
+        _ => sov.base_value(code),
+    }
+}
+
+/// Add a step sequence element for the turns and steps in moves between other elements, if there are enough
+/// difficult turns for at least simple variety.  Body movements cannot be seen in a diagram, so are not assumed.
+fn add_step_sequence(sheet: &mut ScoreSheet, moves: &[TimedMove], sov: &ScaleOfValues) {
+    let analysis = steps::analyze_moves(moves, false);
+    if analysis.variety < Variety::Simple {
+        return;
+    }
+    let code = analysis.element_code();
+    sheet.elements.push(ScoredElement {
+        pos: analysis.turns[0].pos,
+        base_value: sov.base_value(&code),
+        code,
+    });
+}
+
+/// Build the table of base values for (fully expanded) moves.  Elements with no base value in the scale of values are
+/// listed without one, and left out of the total.
+pub(crate) fn score_moves(moves: &[TimedMove], sov: &ScaleOfValues) -> ScoreSheet {
+    let mut sheet = ScoreSheet::default();
+    let mut steps_start = 0;
+    for (idx, timed_mv) in moves.iter().enumerate() {
+        let MoveId::Skating(id) = timed_mv.mv.id() else {
+            continue;
+        };
+        let Some(code) = element_code(id) else {
+            continue;
+        };
+        add_step_sequence(&mut sheet, &moves[steps_start..idx], sov);
+        steps_start = idx + 1;
+        sheet.elements.push(ScoredElement {
+            pos: timed_mv.mv.text_pos().unwrap_or_default(),
+            base_value: base_value(id, &code, sov),
+            code,
+        });
+    }
+    add_step_sequence(&mut sheet, &moves[steps_start..], sov);
+    sheet.total = sheet.elements.iter().filter_map(|e| e.base_value).sum();
+    sheet
+}
@@ -0,0 +1,306 @@
+// Copyright 2025 David Drysdale
+
+//! Step sequence level analysis.
+//!
+//! This gives an estimate of the level that a step sequence can achieve, based on a simplified form of the ISU level
+//! requirements:
+//! - Level 1: simple variety, plus 1 feature.
+//! - Level 2: variety, plus 2 features.
+//! - Level 3: variety, plus 3 features.
+//! - Level 4: complexity, plus 3 features.
+//!
+//! where each type of difficult turn or step counts at most twice towards variety, which needs 5 (simple variety),
+//! 7 (variety) or 11 (complexity) of them.  Mohawks are not difficult steps, so they are listed but do not count.
+
+use crate::{
+    moves::{MoveId, SkatingMoveId},
+    Code, Foot, RotationDirection, TextPosition, TimedMove,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// Number of times each type of turn or step can count towards variety.
+const MAX_PER_TYPE: usize = 2;
+
+/// Number of consecutive different difficult turns on one foot that make a combination.
+const COMBINATION_LEN: usize = 3;
+
+/// Type of turn or step in a step sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TurnType {
+    /// Three turn.
+    ThreeTurn,
+    /// Bracket.
+    Bracket,
+    /// Rocker.
+    Rocker,
+    /// Counter.
+    Counter,
+    /// Twizzle.
+    Twizzle,
+    /// Loop.
+    Loop,
+    /// Mohawk (open or closed).
+    Mohawk,
+    /// Choctaw (open or closed).
+    Choctaw,
+}
+
+impl TurnType {
+    /// All turn types, in display order.
+    pub const ALL: [TurnType; 8] = [
+        TurnType::ThreeTurn,
+        TurnType::Bracket,
+        TurnType::Rocker,
+        TurnType::Counter,
+        TurnType::Twizzle,
+        TurnType::Loop,
+        TurnType::Mohawk,
+        TurnType::Choctaw,
+    ];
+
+    /// Return the turn type for a skating move, if it is a turn or step.
+    pub fn from_id(id: SkatingMoveId) -> Option<Self> {
+        match id {
+            SkatingMoveId::ThreeTurn => Some(TurnType::ThreeTurn),
+            SkatingMoveId::Bracket => Some(TurnType::Bracket),
+            SkatingMoveId::Rocker => Some(TurnType::Rocker),
+            SkatingMoveId::Counter => Some(TurnType::Counter),
+            SkatingMoveId::Twizzle(_count) => Some(TurnType::Twizzle),
+            SkatingMoveId::Loop => Some(TurnType::Loop),
+            SkatingMoveId::OpenMohawk | SkatingMoveId::ClosedMohawk => Some(TurnType::Mohawk),
+            SkatingMoveId::OpenChoctaw | SkatingMoveId::ClosedChoctaw => Some(TurnType::Choctaw),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a difficult turn or step.
+    pub fn is_difficult(&self) -> bool {
+        *self != TurnType::Mohawk
+    }
+}
+
+impl Display for TurnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TurnType::ThreeTurn => write!(f, "three turn"),
+            TurnType::Bracket => write!(f, "bracket"),
+            TurnType::Rocker => write!(f, "rocker"),
+            TurnType::Counter => write!(f, "counter"),
+            TurnType::Twizzle => write!(f, "twizzle"),
+            TurnType::Loop => write!(f, "loop"),
+            TurnType::Mohawk => write!(f, "mohawk"),
+            TurnType::Choctaw => write!(f, "choctaw"),
+        }
+    }
+}
+
+/// Turn or step within a step sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Turn {
+    /// Position of the move in the input.
+    pub pos: TextPosition,
+    /// Type of turn or step.
+    pub turn: TurnType,
+    /// Foot, direction and edge at the start of the move.
+    pub code: Code,
+    /// Direction of rotation.
+    pub direction: RotationDirection,
+}
+
+/// Variety of difficult turns and steps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Variety {
+    /// Not enough difficult turns and steps for simple variety.
+    #[default]
+    None,
+    /// Simple variety.
+    Simple,
+    /// Variety.
+    Variety,
+    /// Complexity.
+    Complexity,
+}
+
+/// Result of analysing the turns and steps in a step sequence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StepAnalysis {
+    /// Turns and steps, in order.
+    pub turns: Vec<Turn>,
+    /// Number of turns of each type, in the order of [`TurnType::ALL`].
+    pub counts: Vec<(TurnType, usize)>,
+    /// Number of turns rotating clockwise.
+    pub clockwise: usize,
+    /// Number of turns rotating anti-clockwise.
+    pub anticlockwise: usize,
+    /// Variety of difficult turns and steps.
+    pub variety: Variety,
+    /// Whether there are difficult turns rotating in both directions, each for at least a third of them.
+    pub both_directions: bool,
+    /// Whether body movements are used (which is assumed, as it cannot be seen in a diagram).
+    pub body_movements: bool,
+    /// Whether there are combinations of three different difficult turns on each foot.
+    pub combinations: bool,
+    /// Estimated level, where 0 is the base level.
+    pub level: u32,
+}
+
+impl StepAnalysis {
+    /// Number of level features achieved.
+    pub fn features(&self) -> usize {
+        [self.both_directions, self.body_movements, self.combinations]
+            .iter()
+            .filter(|f| **f)
+            .count()
+    }
+
+    /// Return the ISU element code for a step sequence at the estimated level, e.g. "StSq4" or "StSqB".
+    pub fn element_code(&self) -> String {
+        match self.level {
+            0 => "StSqB".to_string(),
+            level => format!("StSq{level}"),
+        }
+    }
+}
+
+impl Display for StepAnalysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (turn, count) in &self.counts {
+            writeln!(f, "{:<24} {count:>3}", turn.to_string())?;
+        }
+        writeln!(f, "{:<24} {:>3}", "clockwise", self.clockwise)?;
+        writeln!(f, "{:<24} {:>3}", "anti-clockwise", self.anticlockwise)?;
+        writeln!(f, "variety: {:?}", self.variety)?;
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        writeln!(f, "both directions: {}", yes_no(self.both_directions))?;
+        writeln!(
+            f,
+            "body movements (assumed): {}",
+            yes_no(self.body_movements)
+        )?;
+        writeln!(
+            f,
+            "turn combinations on each foot: {}",
+            yes_no(self.combinations)
+        )?;
+        writeln!(f, "estimated level: {}", self.element_code())
+    }
+}
+
+/// Return the direction of rotation for a turn.  Brackets and counters rotate against the curve of the entry edge;
+/// all other turns and steps rotate with it.
+///
+/// The net rotation of the move's [`crate::Move::transition`] is not used, because it follows the curve of the
+/// edges either side of the turn, and so is the same for (say) a three turn and a bracket from the same edge.
+fn rotation_direction(turn: TurnType, code: Code) -> RotationDirection {
+    let with_curve = code.curve_direction().unwrap_or_default();
+    match (turn, with_curve) {
+        (TurnType::Bracket | TurnType::Counter, RotationDirection::Clockwise) => {
+            RotationDirection::AntiClockwise
+        }
+        (TurnType::Bracket | TurnType::Counter, RotationDirection::AntiClockwise) => {
+            RotationDirection::Clockwise
+        }
+        (_, direction) => direction,
+    }
+}
+
+/// Analyse the turns and steps in (fully expanded) moves.
+pub(crate) fn analyze_moves(moves: &[TimedMove], body_movements: bool) -> StepAnalysis {
+    let mut analysis = StepAnalysis {
+        body_movements,
+        ..Default::default()
+    };
+
+    // Track runs of consecutive difficult turns on the same foot.
+    let mut run: Option<(Foot, Vec<TurnType>)> = None;
+    let mut left_combination = false;
+    let mut right_combination = false;
+    for timed_mv in moves {
+        let MoveId::Skating(id) = timed_mv.mv.id() else {
+            // Pseudo-moves do not interrupt a run of turns.
+            continue;
+        };
+        let (Some(turn), Some(code)) = (TurnType::from_id(id), timed_mv.mv.start()) else {
+            run = None;
+            continue;
+        };
+        let direction = rotation_direction(turn, code);
+        match direction {
+            RotationDirection::Clockwise => analysis.clockwise += 1,
+            RotationDirection::AntiClockwise => analysis.anticlockwise += 1,
+        }
+        analysis.turns.push(Turn {
+            pos: timed_mv.mv.text_pos().unwrap_or_default(),
+            turn,
+            code,
+            direction,
+        });
+
+        if !turn.is_difficult() {
+            run = None;
+            continue;
+        }
+        let mut turns = match run.take() {
+            Some((foot, turns)) if foot == code.foot => turns,
+            _ => Vec::new(),
+        };
+        turns.push(turn);
+        // A combination needs the most recent turns to all be different.
+        let combination = turns.len() >= COMBINATION_LEN && {
+            let recent = &turns[turns.len() - COMBINATION_LEN..];
+            recent
+                .iter()
+                .enumerate()
+                .all(|(idx, turn)| !recent[..idx].contains(turn))
+        };
+        run = Some((code.foot, turns));
+        if combination {
+            match code.foot {
+                Foot::Left => left_combination = true,
+                Foot::Right => right_combination = true,
+                Foot::Both => {}
+            }
+        }
+    }
+
+    analysis.counts = TurnType::ALL
+        .iter()
+        .map(|turn| {
+            let count = analysis.turns.iter().filter(|t| t.turn == *turn).count();
+            (*turn, count)
+        })
+        .collect();
+    let difficult: usize = analysis
+        .counts
+        .iter()
+        .filter(|(turn, _count)| turn.is_difficult())
+        .map(|(_turn, count)| (*count).min(MAX_PER_TYPE))
+        .sum();
+    analysis.variety = match difficult {
+        11.. => Variety::Complexity,
+        7.. => Variety::Variety,
+        5.. => Variety::Simple,
+        _ => Variety::None,
+    };
+
+    let (mut clockwise, mut anticlockwise) = (0, 0);
+    for turn in analysis.turns.iter().filter(|t| t.turn.is_difficult()) {
+        match turn.direction {
+            RotationDirection::Clockwise => clockwise += 1,
+            RotationDirection::AntiClockwise => anticlockwise += 1,
+        }
+    }
+    let total = clockwise + anticlockwise;
+    analysis.both_directions = total > 0 && clockwise * 3 >= total && anticlockwise * 3 >= total;
+    analysis.combinations = left_combination && right_combination;
+
+    let features = analysis.features();
+    analysis.level = match analysis.variety {
+        Variety::Complexity if features >= 3 => 4,
+        Variety::Complexity | Variety::Variety if features >= 3 => 3,
+        Variety::Complexity | Variety::Variety if features >= 2 => 2,
+        Variety::Complexity | Variety::Variety | Variety::Simple if features >= 1 => 1,
+        _ => 0,
+    };
+    analysis
+}
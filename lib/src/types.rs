@@ -4,8 +4,11 @@
 
 use crate::{
     code,
-    moves::{cross_transition, pre_transition, wide_transition},
-    MoveParam, ParseError,
+    moves::{
+        continuity_warning, cross_transition, cross_transition_warning, jump, pre_transition,
+        wide_transition,
+    },
+    MoveParam, ParseError, WarningKind,
 };
 use log::trace;
 use serde::Serialize;
@@ -306,7 +309,7 @@ impl TextPosition {
 /// Number of rotations in a jump.
 #[allow(missing_docs)]
 #[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub enum JumpCount {
     Single = 1,
     Double = 2,
@@ -338,6 +341,148 @@ impl TryFrom<u32> for JumpCount {
     }
 }
 
+/// Kind of jump, independent of the number of rotations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum JumpKind {
+    /// Salchow.
+    Salchow,
+    /// Toe loop.
+    ToeLoop,
+    /// Loop.
+    Loop,
+    /// Flip.
+    Flip,
+    /// Lutz.
+    Lutz,
+    /// Axel.
+    Axel,
+    /// Euler (half loop).
+    Euler,
+}
+
+impl Display for JumpKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            Self::Salchow => jump::Salchow::JUMP,
+            Self::ToeLoop => jump::ToeLoop::JUMP,
+            Self::Loop => jump::Loop::JUMP,
+            Self::Flip => jump::Flip::JUMP,
+            Self::Lutz => jump::Lutz::JUMP,
+            Self::Axel => jump::Axel::JUMP,
+            Self::Euler => jump::Euler::JUMP,
+        };
+        write!(f, "{code}")
+    }
+}
+
+/// Maximum number of jumps in a jump combination or sequence.
+pub const MAX_COMBO_JUMPS: usize = 3;
+
+/// Combination (or sequence) of jumps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct JumpCombo {
+    // Invariant: 2 <= `len` <= `MAX_COMBO_JUMPS`.
+    jumps: [(JumpKind, JumpCount); MAX_COMBO_JUMPS],
+    len: usize,
+    /// Whether this is a jump sequence rather than a combination.
+    pub sequence: bool,
+}
+
+impl JumpCombo {
+    /// Create a combination of two jumps.
+    pub const fn pair(first: (JumpKind, JumpCount), second: (JumpKind, JumpCount)) -> Self {
+        Self {
+            jumps: [first, second, second],
+            len: 2,
+            sequence: false,
+        }
+    }
+
+    /// Create a combination or sequence from between 2 and [`MAX_COMBO_JUMPS`] jumps.
+    pub fn new(jumps: &[(JumpKind, JumpCount)], sequence: bool) -> Option<Self> {
+        if jumps.len() < 2 || jumps.len() > MAX_COMBO_JUMPS {
+            return None;
+        }
+        let mut combo = Self::pair(jumps[0], jumps[1]);
+        combo.jumps[..jumps.len()].copy_from_slice(jumps);
+        combo.len = jumps.len();
+        combo.sequence = sequence;
+        Some(combo)
+    }
+
+    /// Return the constituent jumps, in order.
+    pub fn jumps(&self) -> &[(JumpKind, JumpCount)] {
+        &self.jumps[..self.len]
+    }
+}
+
+impl Display for JumpCombo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Emit the ISU abbreviation, e.g. "3Lz+3T" or "3A+2A+SEQ".
+        for (idx, (kind, count)) in self.jumps().iter().enumerate() {
+            if idx > 0 {
+                write!(f, "+")?;
+            }
+            write!(f, "{count}{kind}")?;
+        }
+        if self.sequence {
+            write!(f, "+{}", jump::Combo::SEQUENCE)?;
+        }
+        Ok(())
+    }
+}
+
+/// Basic body position during a spin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SpinPosition {
+    /// Upright spin.
+    Upright,
+    /// Sit spin.
+    Sit,
+    /// Camel spin.
+    Camel,
+    /// Layback spin.
+    Layback,
+    /// Combination of positions.
+    Combination,
+}
+
+impl Display for SpinPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Upright => write!(f, "U"),
+            Self::Sit => write!(f, "S"),
+            Self::Camel => write!(f, "C"),
+            Self::Layback => write!(f, "L"),
+            Self::Combination => write!(f, "Co"),
+        }
+    }
+}
+
+/// Kind of spin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SpinKind {
+    /// Basic position.
+    pub position: SpinPosition,
+    /// Whether the spin has a flying entry.
+    pub flying: bool,
+    /// Whether the spin includes a change of foot.
+    pub change_foot: bool,
+}
+
+impl Display for SpinKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Emit the ISU abbreviation, e.g. "FCCoSp".
+        if self.flying {
+            write!(f, "F")?;
+        }
+        if self.change_foot {
+            write!(f, "C")?;
+        }
+        write!(f, "{}Sp", self.position)
+    }
+}
+
 /// Helper macro to create [`Position`] instance from bare integers.
 #[macro_export]
 macro_rules! pos {
@@ -729,6 +874,17 @@ impl Display for Code {
 }
 
 impl Code {
+    /// Return the direction that an edge with this code curves in, or `None` for flats and two-footed codes.
+    pub fn curve_direction(&self) -> Option<RotationDirection> {
+        match self {
+            code!(LFO) | code!(RFI) | code!(LBI) | code!(RBO) => {
+                Some(RotationDirection::AntiClockwise)
+            }
+            code!(RFO) | code!(LFI) | code!(RBI) | code!(LBO) => Some(RotationDirection::Clockwise),
+            _ => None,
+        }
+    }
+
     /// Return the equivalent code for the other foot.
     pub fn opposite(&self) -> Self {
         if self.foot == Foot::Both {
@@ -778,6 +934,20 @@ impl PreTransition {
         }
     }
 
+    /// Return any warning about performing the pre-transition from `from` to the `start` position for a move.  If
+    /// `strict` is set, also check for physically impossible changes of foot, direction or edge.
+    pub fn warning(&self, from: Code, start: Code, strict: bool) -> Option<WarningKind> {
+        match self {
+            PreTransition::CrossFront | PreTransition::CrossBehind => {
+                cross_transition_warning(from, start)
+            }
+            PreTransition::Normal | PreTransition::Wide if strict => {
+                continuity_warning(from, start)
+            }
+            PreTransition::Normal | PreTransition::Wide => None,
+        }
+    }
+
     /// Return label text.
     pub fn label(&self) -> Option<&'static str> {
         match self {
@@ -852,6 +1022,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_curve_direction() {
+        for code in [code!(LFO), code!(RFI), code!(LBI), code!(RBO)] {
+            assert_eq!(
+                code.curve_direction(),
+                Some(RotationDirection::AntiClockwise)
+            );
+            assert_eq!(
+                code.opposite().curve_direction(),
+                Some(RotationDirection::Clockwise)
+            );
+        }
+        for code in [code!(LF), code!(RB), code!(BF), code!(BFL)] {
+            assert_eq!(code.curve_direction(), None);
+        }
+    }
+
     #[test]
     fn test_add_margin() {
         let mut bounds = Bounds {
@@ -0,0 +1,156 @@
+// Copyright 2025 David Drysdale
+
+//! Warning type.
+
+use crate::{CallSite, Code, JumpCount, JumpKind, TextPosition};
+use std::fmt::{self, Display, Formatter};
+
+/// Kind of questionable (but legal) input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// Transition with feet crossing over, but with no change of foot.
+    CrossNoFootChange,
+    /// Transition with feet crossing over, starting from two feet.
+    CrossFromTwoFeet,
+    /// Transition with feet crossing over, ending on two feet.
+    CrossToTwoFeet,
+    /// Change of direction on the same foot, with no turn (strict mode only).
+    SameFootDirectionChange {
+        /// Code at the end of the previous move.
+        from: Code,
+        /// Code at the start of the move.
+        to: Code,
+    },
+    /// Change of edge on the same foot, with no change of edge move (strict mode only).
+    SameFootEdgeChange {
+        /// Code at the end of the previous move.
+        from: Code,
+        /// Code at the start of the move.
+        to: Code,
+    },
+    /// More jump elements than the rule set allows.
+    TooManyJumpElements {
+        /// Maximum number of jump elements.
+        max: usize,
+    },
+    /// More jump combinations and sequences than the rule set allows.
+    TooManyCombinations {
+        /// Maximum number of jump combinations and sequences.
+        max: usize,
+    },
+    /// More than one jump combination or sequence with three jumps.
+    TooManyThreeJumpCombinations,
+    /// Jump sequence in a segment that does not allow them.
+    SequenceNotAllowed,
+    /// Jump with more rotations than the rule set allows.
+    TooManyRotations {
+        /// Maximum number of rotations.
+        max: JumpCount,
+    },
+    /// Jump executed more times than the rule set allows.
+    JumpRepeated {
+        /// Kind of jump.
+        kind: JumpKind,
+        /// Number of rotations.
+        count: JumpCount,
+    },
+    /// Repeated triple or quad jump where neither execution is in a combination or sequence.
+    RepeatNotInCombination {
+        /// Kind of jump.
+        kind: JumpKind,
+        /// Number of rotations.
+        count: JumpCount,
+    },
+    /// More distinct triple and quad jumps repeated than the rule set allows.
+    TooManyRepeatedJumps {
+        /// Maximum number of repeated triple and quad jumps.
+        max: usize,
+    },
+    /// More distinct quad jumps repeated than the rule set allows.
+    TooManyRepeatedQuads {
+        /// Maximum number of repeated quad jumps.
+        max: usize,
+    },
+    /// No Axel-type jump element.
+    MissingAxel,
+    /// Move whose path goes outside the rink.
+    OutsideRink,
+}
+
+impl Display for WarningKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WarningKind::CrossNoFootChange => write!(f, "cross transition but no foot change"),
+            WarningKind::CrossFromTwoFeet => write!(f, "cross transition from two feet"),
+            WarningKind::CrossToTwoFeet => write!(f, "cross transition to two feet"),
+            WarningKind::SameFootDirectionChange { from, to } => {
+                write!(f, "change of direction from {from} to {to} with no turn")
+            }
+            WarningKind::SameFootEdgeChange { from, to } => {
+                write!(
+                    f,
+                    "edge changes from {from} to {to} on the same foot without a change-of-edge move"
+                )
+            }
+            WarningKind::TooManyJumpElements { max } => {
+                write!(f, "more than {max} jump elements")
+            }
+            WarningKind::TooManyCombinations { max } => {
+                write!(f, "more than {max} jump combinations or sequences")
+            }
+            WarningKind::TooManyThreeJumpCombinations => {
+                write!(f, "more than one combination or sequence with three jumps")
+            }
+            WarningKind::SequenceNotAllowed => write!(f, "jump sequence not allowed"),
+            WarningKind::TooManyRotations { max } => {
+                write!(f, "jump with more than {max} rotations")
+            }
+            WarningKind::JumpRepeated { kind, count } => {
+                write!(f, "{count}{kind} repeated too often")
+            }
+            WarningKind::RepeatNotInCombination { kind, count } => {
+                write!(f, "repeated {count}{kind} not in a combination or sequence")
+            }
+            WarningKind::TooManyRepeatedJumps { max } => {
+                write!(f, "more than {max} triple or quad jumps repeated")
+            }
+            WarningKind::TooManyRepeatedQuads { max } => {
+                write!(f, "more than {max} quad jumps repeated")
+            }
+            WarningKind::MissingAxel => write!(f, "no Axel-type jump element"),
+            WarningKind::OutsideRink => write!(f, "move goes outside the rink"),
+        }
+    }
+}
+
+/// Warning about questionable (but legal) input, which does not prevent generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// Position of the questionable input.
+    pub pos: TextPosition,
+    /// Kind of warning.
+    pub kind: WarningKind,
+    /// Name of the included file that the position refers to; [`None`] for the main input.
+    pub file: Option<String>,
+    /// Invocations of named sequences that the position was reached through, innermost first.
+    pub calls: Vec<CallSite>,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        write!(
+            f,
+            "{}:{}: warning: {}",
+            self.pos.row + 1,
+            self.pos.col + 1,
+            self.kind
+        )?;
+        for call in &self.calls {
+            write!(f, " ({call})")?;
+        }
+        Ok(())
+    }
+}
@@ -37,6 +37,7 @@ fn test_parse_success() {
     let extension = OsStr::new("skate");
     let eg_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/pass").to_string();
     let eg_path = std::path::PathBuf::from(eg_dir);
+    let resolver = skreate::FileResolver::new(&eg_path);
 
     for entry in read_dir(eg_path).unwrap() {
         let entry = entry.unwrap();
@@ -47,12 +48,59 @@ fn test_parse_success() {
         let mut reader = File::open(path.clone()).unwrap();
         let mut input = String::new();
         reader.read_to_string(&mut input).unwrap();
-        let result = skreate::generate(&input);
+        let result = skreate::generate_with_resolver(&input, &resolver);
+        assert!(result.is_ok());
+        let result = skreate::generate_animated(&input, &resolver, skreate::DEFAULT_TEMPO);
         assert!(result.is_ok());
         info!("file '{path:?}' parsed successfully");
     }
 }
 
+#[cfg(feature = "png")]
+#[test]
+fn test_png() {
+    let _ = env_logger::try_init();
+    for info in skreate::moves::INFO {
+        let png = skreate::generate_png(
+            info.example,
+            &skreate::MemoryResolver::default(),
+            skreate::PngSize::Width(200),
+        )
+        .unwrap_or_else(|e| panic!("failed to generate PNG for {}: {e}", info.name));
+        assert!(png.starts_with(b"\x89PNG"), "for '{}'", info.name);
+    }
+}
+
+#[cfg(feature = "pdf")]
+#[test]
+fn test_pdf() {
+    let _ = env_logger::try_init();
+    let resolver = skreate::MemoryResolver::default();
+    for info in skreate::moves::INFO {
+        let pdf = skreate::generate_pdf(info.example, &resolver, skreate::PdfOptions::default())
+            .unwrap_or_else(|e| panic!("failed to generate PDF for {}: {e}", info.name));
+        assert!(pdf.starts_with(b"%PDF"), "for '{}'", info.name);
+    }
+    let opts = skreate::PdfOptions {
+        page: skreate::PageSize::Letter,
+        scale: skreate::PdfScale::Ratio(100),
+    };
+    let pdf = skreate::generate_pdf("LFO;RFI", &resolver, opts).unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+    // Failures in producing the output have no position in the input.
+    let err = skreate::generate_pdf("Rink", &resolver, opts).unwrap_err();
+    assert!(matches!(err, skreate::OutputError::Render(_)));
+    assert!(err.to_string().starts_with("diagram needs "), "{err}");
+    let err = skreate::generate_pdf("XYZ;ABC", &resolver, opts).unwrap_err();
+    assert!(matches!(err, skreate::OutputError::Parse(ref errs) if errs.len() == 2));
+
+    // Warnings come back alongside the output.
+    let input = "Rink[width=1000,length=1000]\nWarp[x=20,y=20,dir=315]\nLFO";
+    let (pdf, warnings) = skreate::generate_pdf_with_warnings(input, &resolver, opts).unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+    assert_eq!(warnings.len(), 1);
+}
+
 fn regenerate() -> bool {
     !std::env::var("SKREATE_REGENERATE")
         .unwrap_or_default()
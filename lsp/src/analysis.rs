@@ -0,0 +1,425 @@
+// Copyright 2025 David Drysdale
+
+//! Language features for skreate input, independent of the LSP transport.
+
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticRelatedInformation,
+    DiagnosticSeverity, Documentation, Location, MarkupContent, MarkupKind, Position, Range,
+    TextEdit, Uri,
+};
+use skreate::{
+    moves::{self, MoveId, PseudoMoveId},
+    CallSite, ParseError, Resolver, TextPosition, Warning,
+};
+
+/// Pseudo-moves that are not included in [`moves::INFO`] but can still be completed.
+const EXTRA_PSEUDO_MOVES: &[PseudoMoveId] = &[
+    PseudoMoveId::Define,
+    PseudoMoveId::DefineEnd,
+    PseudoMoveId::Call,
+    PseudoMoveId::Include,
+    PseudoMoveId::Let,
+];
+
+/// Return the given (zero-indexed) row of the text, without any line terminator.
+fn line(text: &str, row: usize) -> &str {
+    let line = text.split('\n').nth(row).unwrap_or("");
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// Convert a byte offset within a row of the text into an LSP position, which counts UTF-16 code units.
+fn to_lsp(text: &str, row: usize, col: usize) -> Position {
+    let line = line(text, row);
+    let col = (0..=col.min(line.len()))
+        .rev()
+        .find(|idx| line.is_char_boundary(*idx))
+        .unwrap_or(0);
+    Position::new(row as u32, line[..col].encode_utf16().count() as u32)
+}
+
+/// Convert an LSP position into a row and byte offset within that row.
+fn from_lsp(text: &str, pos: Position) -> (usize, usize) {
+    let row = pos.line as usize;
+    let mut units = 0;
+    for (idx, ch) in line(text, row).char_indices() {
+        if units >= pos.character as usize {
+            return (row, idx);
+        }
+        units += ch.len_utf16();
+    }
+    (row, line(text, row).len())
+}
+
+/// Convert a position in the text into an LSP range, covering at least one character.
+fn range(text: &str, pos: &TextPosition) -> Range {
+    Range::new(
+        to_lsp(text, pos.row, pos.col),
+        to_lsp(text, pos.row, pos.col + pos.count.max(1)),
+    )
+}
+
+/// Return the position of the `Include` move that pulls in `file`, if there is one in the text.
+fn include_pos(text: &str, file: &str) -> Option<TextPosition> {
+    let quoted = format!("\"{file}\"");
+    skreate::move_infos(text)
+        .into_iter()
+        .find(|(pos, info)| {
+            info.id == MoveId::Pseudo(PseudoMoveId::Include)
+                && line(text, pos.row)
+                    .get(pos.col..pos.col + pos.count)
+                    .is_some_and(|mv| mv.contains(&quoted))
+        })
+        .map(|(pos, _info)| pos)
+}
+
+/// Return the range for `pos` in `file`.  Positions in included files are reported against the corresponding
+/// `Include` move, or the start of the text if that cannot be found.
+fn file_range(text: &str, pos: &TextPosition, file: Option<&str>) -> Range {
+    match file {
+        None => range(text, pos),
+        Some(file) => include_pos(text, file)
+            .map(|pos| range(text, &pos))
+            .unwrap_or_default(),
+    }
+}
+
+/// Return the range and message for a diagnostic at `pos` in `file`.  Positions in included files are reported
+/// as for [`file_range`], with the message including the full location.
+fn located(
+    text: &str,
+    pos: &TextPosition,
+    file: Option<&str>,
+    full: String,
+    msg: String,
+) -> (Range, String) {
+    let msg = if file.is_some() { full } else { msg };
+    (file_range(text, pos, file), msg)
+}
+
+/// Return related information that points at each of the invocations of named sequences that led to a diagnostic.
+fn related(uri: &Uri, text: &str, calls: &[CallSite]) -> Option<Vec<DiagnosticRelatedInformation>> {
+    if calls.is_empty() {
+        return None;
+    }
+    let related = calls
+        .iter()
+        .map(|call| DiagnosticRelatedInformation {
+            location: Location::new(
+                uri.clone(),
+                file_range(text, &call.pos, call.file.as_deref()),
+            ),
+            message: call.to_string(),
+        })
+        .collect();
+    Some(related)
+}
+
+/// Convert an error into a diagnostic.
+fn diagnostic(uri: &Uri, text: &str, err: &ParseError) -> Diagnostic {
+    let (range, message) = located(
+        text,
+        &err.pos,
+        err.file.as_deref(),
+        err.to_string(),
+        err.msg.clone(),
+    );
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("skreate".to_string()),
+        message,
+        related_information: related(uri, text, &err.calls),
+        ..Default::default()
+    }
+}
+
+/// Convert a warning into a diagnostic.
+fn warning_diagnostic(uri: &Uri, text: &str, warning: &Warning) -> Diagnostic {
+    let (range, message) = located(
+        text,
+        &warning.pos,
+        warning.file.as_deref(),
+        warning.to_string(),
+        warning.kind.to_string(),
+    );
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::WARNING),
+        source: Some("skreate".to_string()),
+        message,
+        related_information: related(uri, text, &warning.calls),
+        ..Default::default()
+    }
+}
+
+/// Return diagnostics for the text of the document at `uri`, using `resolver` to find the contents of any included
+/// files.
+pub fn diagnostics(uri: &Uri, text: &str, resolver: &dyn Resolver) -> Vec<Diagnostic> {
+    match skreate::generate_with_warnings(text, resolver) {
+        Ok((_svg, warnings)) => warnings
+            .iter()
+            .map(|warning| warning_diagnostic(uri, text, warning))
+            .collect(),
+        Err(errs) => errs.iter().map(|err| diagnostic(uri, text, err)).collect(),
+    }
+}
+
+/// Return the bounds of the parameter name or move code that includes byte offset `col` in `line`.
+fn word_at(line: &str, col: usize) -> (usize, usize) {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let start = line[..col]
+        .rfind(|c| !is_word(c))
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    let end = line[col..]
+        .find(|c| !is_word(c))
+        .map(|idx| col + idx)
+        .unwrap_or(line.len());
+    (start, end)
+}
+
+/// Markdown documentation for a move.
+fn move_doc(info: &moves::Info) -> String {
+    let mut doc = format!(
+        "**{}**: {}\n\nExample: `{}`",
+        info.name, info.summary, info.example
+    );
+    if !info.params.is_empty() {
+        doc += "\n\nParameters:";
+        for param in info.params {
+            doc += &format!("\n- `{}`: {}", param.name, param.doc);
+        }
+    }
+    doc
+}
+
+/// Markdown documentation for a move parameter.
+fn param_doc(param: &skreate::params::Info) -> String {
+    format!(
+        "**{}**: {}\n\nDefault: `{}`, range: {}",
+        param.name, param.doc, param.default, param.range
+    )
+}
+
+/// Return Markdown hover documentation for whatever is at `pos` in the text.
+pub fn hover(text: &str, pos: Position) -> Option<String> {
+    let (row, col) = from_lsp(text, pos);
+    let (mv_pos, info) = skreate::move_infos(text)
+        .into_iter()
+        .find(|(mv_pos, _info)| {
+            mv_pos.row == row && col >= mv_pos.col && col < mv_pos.col + mv_pos.count
+        })?;
+
+    // Describe the parameter if the cursor is on a parameter name inside the brackets.
+    let line = line(text, row);
+    if line[mv_pos.col..col].contains('[') {
+        let (start, end) = word_at(line, col);
+        if let Some(param) = info.params.iter().find(|p| p.name == &line[start..end]) {
+            return Some(param_doc(param));
+        }
+    }
+    Some(move_doc(info))
+}
+
+/// Build a completion item.
+fn item(label: &str, kind: CompletionItemKind, detail: &str, doc: String) -> CompletionItem {
+    CompletionItem {
+        label: label.to_string(),
+        kind: Some(kind),
+        detail: Some(detail.to_string()),
+        documentation: Some(Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: doc,
+        })),
+        ..Default::default()
+    }
+}
+
+/// Return completions for the move names.
+fn move_completions() -> Vec<CompletionItem> {
+    let extras = EXTRA_PSEUDO_MOVES.iter().map(|id| id.info());
+    moves::INFO
+        .iter()
+        .chain(extras)
+        .map(|info| match info.id {
+            MoveId::Skating(_) => item(
+                info.example,
+                CompletionItemKind::FUNCTION,
+                info.name,
+                move_doc(info),
+            ),
+            MoveId::Pseudo(_) => item(
+                info.name,
+                CompletionItemKind::KEYWORD,
+                info.summary,
+                move_doc(info),
+            ),
+        })
+        .collect()
+}
+
+/// Return completions for the parameter names of the move described by `mv`.
+fn param_completions(mv: &str) -> Vec<CompletionItem> {
+    let Some((_pos, info)) = skreate::move_infos(mv.trim()).into_iter().last() else {
+        return Vec::new();
+    };
+    info.params
+        .iter()
+        .map(|param| {
+            let mut item = item(
+                param.name,
+                CompletionItemKind::PROPERTY,
+                param.doc,
+                param_doc(param),
+            );
+            item.insert_text = Some(format!("{}=", param.name));
+            item
+        })
+        .collect()
+}
+
+/// Return completions for `pos` in the text: parameter names inside a move's brackets, otherwise move names.
+pub fn completions(text: &str, pos: Position) -> Vec<CompletionItem> {
+    let (row, col) = from_lsp(text, pos);
+    let before = &line(text, row)[..col];
+    let current = match before.rfind(';') {
+        Some(idx) => &before[idx + 1..],
+        None => before,
+    };
+    match current.rfind('[') {
+        Some(open) if !current[open..].contains(']') => param_completions(&current[..open]),
+        _ => move_completions(),
+    }
+}
+
+/// Return the edits that convert the text into canonical form, or [`None`] if it fails to parse.
+pub fn format(text: &str) -> Option<Vec<TextEdit>> {
+    let canonical = skreate::canonicalize(text).ok()?;
+    if canonical == text {
+        return Some(Vec::new());
+    }
+    let rows = text.split('\n').count();
+    let end = to_lsp(text, rows - 1, usize::MAX);
+    Some(vec![TextEdit::new(
+        Range::new(Position::new(0, 0), end),
+        canonical,
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skreate::MemoryResolver;
+
+    #[test]
+    fn test_positions() {
+        let text = "LFO;\n  \"é\" RFI\r\nLBO";
+        assert_eq!(to_lsp(text, 0, 1), Position::new(0, 1));
+        assert_eq!(to_lsp(text, 1, 5), Position::new(1, 4));
+        assert_eq!(to_lsp(text, 1, 100), Position::new(1, 9));
+        assert_eq!(from_lsp(text, Position::new(1, 4)), (1, 5));
+        assert_eq!(from_lsp(text, Position::new(1, 100)), (1, 10));
+        assert_eq!(from_lsp(text, Position::new(2, 2)), (2, 2));
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let uri = "file:///tmp/test.skate".parse::<Uri>().unwrap();
+        let resolver = MemoryResolver::default();
+        assert!(diagnostics(&uri, "LFO;RFI", &resolver).is_empty());
+        let diags = diagnostics(&uri, "LFO;xf-LFI", &resolver);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diags[0].range,
+            Range::new(Position::new(0, 4), Position::new(0, 10))
+        );
+        let diags = diagnostics(&uri, "LFO;\nRFI;LFO-Xyz;LFO[len=-1]", &resolver);
+        assert_eq!(diags.len(), 2);
+        assert_eq!(
+            diags[0].range,
+            Range::new(Position::new(1, 8), Position::new(1, 11))
+        );
+        assert_eq!(
+            diags[1].range,
+            Range::new(Position::new(1, 12), Position::new(1, 23))
+        );
+
+        let mut resolver = MemoryResolver::default();
+        resolver.insert("bad.skate", "LFO;\nXYZ");
+        let diags = diagnostics(&uri, "LFO;\nInclude[file=\"bad.skate\"]", &resolver);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].range,
+            Range::new(Position::new(1, 0), Position::new(1, 25))
+        );
+        assert!(diags[0].message.starts_with("bad.skate:2:1:"));
+
+        // Problems inside a named sequence also point at the invocation.
+        let resolver = MemoryResolver::default();
+        let diags = diagnostics(
+            &uri,
+            "Define[name=\"a\"]\nxf-LFI\nEnd\nLFO;Call[name=\"a\"]",
+            &resolver,
+        );
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].range,
+            Range::new(Position::new(1, 0), Position::new(1, 6))
+        );
+        let related = diags[0].related_information.as_ref().unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].location.uri, uri);
+        assert_eq!(
+            related[0].location.range,
+            Range::new(Position::new(3, 4), Position::new(3, 18))
+        );
+        assert_eq!(related[0].message, "in \"a\" invoked at 4:5");
+    }
+
+    #[test]
+    fn test_hover() {
+        let text = "LFO;RFI-Br[len=200]";
+        assert!(hover(text, Position::new(0, 1))
+            .unwrap()
+            .starts_with("**Edge**"));
+        assert!(hover(text, Position::new(0, 5))
+            .unwrap()
+            .contains("Bracket"));
+        let doc = hover(text, Position::new(0, 12)).unwrap();
+        assert!(doc.starts_with("**len**"), "{doc}");
+        assert_eq!(hover(text, Position::new(0, 3)), None);
+        assert!(hover("XYZ;LFO", Position::new(0, 5)).is_some());
+    }
+
+    #[test]
+    fn test_completions() {
+        let names = |items: Vec<CompletionItem>| {
+            items.into_iter().map(|item| item.label).collect::<Vec<_>>()
+        };
+        let moves = names(completions("LFO;", Position::new(0, 4)));
+        assert!(moves.contains(&"LFO3".to_string()));
+        assert!(moves.contains(&"Title".to_string()));
+        assert!(moves.contains(&"Call".to_string()));
+
+        let params = names(completions("LFO;Title[", Position::new(0, 10)));
+        assert!(params.contains(&"text".to_string()));
+        assert!(!params.contains(&"len".to_string()));
+
+        let params = names(completions("LFO;RFI-Br[len=200,", Position::new(0, 19)));
+        assert!(params.contains(&"len".to_string()));
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format("LFO;RFI"), Some(Vec::new()));
+        assert_eq!(format("LFO;XYZ"), None);
+        let edits = format("LFO[len = 450]\n# comment\nRFI").unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(
+            edits[0].range,
+            Range::new(Position::new(0, 0), Position::new(2, 3))
+        );
+        assert_eq!(edits[0].new_text, "LFO\n# comment\nRFI");
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright 2025 David Drysdale
+
+//! Language server for skreate input, speaking LSP over stdio.
+use anyhow::Result;
+use log::{debug, info, warn};
+use lsp_server::{Connection, ExtractError, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{Completion, Formatting, HoverRequest},
+    CompletionOptions, CompletionResponse, Hover, HoverContents, HoverProviderCapability,
+    MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+mod analysis;
+
+/// State of the server.
+struct Server {
+    connection: Connection,
+    /// Current contents of open documents.
+    docs: HashMap<Uri, String>,
+}
+
+/// Return the directory holding the document, for resolving included files.
+fn base_dir(uri: &Uri) -> PathBuf {
+    let is_file = uri
+        .scheme()
+        .is_some_and(|scheme| scheme.as_str().eq_ignore_ascii_case("file"));
+    if is_file {
+        let path = uri.path().as_estr().decode().into_string_lossy();
+        if let Some(dir) = std::path::Path::new(path.as_ref()).parent() {
+            return dir.to_path_buf();
+        }
+    }
+    PathBuf::from(".")
+}
+
+/// Extract the parameters of a request of type `R`, if it is one.  A request of type `R` with invalid parameters
+/// gives the error response to send back.
+fn cast<R>(req: &Request) -> std::result::Result<Option<(RequestId, R::Params)>, Response>
+where
+    R: lsp_types::request::Request,
+    R::Params: serde::de::DeserializeOwned,
+{
+    match req.clone().extract(R::METHOD) {
+        Ok(result) => Ok(Some(result)),
+        Err(ExtractError::MethodMismatch(_)) => Ok(None),
+        Err(ExtractError::JsonError { method, error }) => {
+            warn!("invalid {method} request: {error}");
+            Err(Response::new_err(
+                req.id.clone(),
+                lsp_server::ErrorCode::InvalidParams as i32,
+                format!("invalid {method} params: {error}"),
+            ))
+        }
+    }
+}
+
+/// Extract the parameters of a notification of type `N`, if it is one.  Notifications with invalid parameters are
+/// logged and skipped.
+fn cast_notification<N>(note: &Notification) -> Option<N::Params>
+where
+    N: NotificationTrait,
+    N::Params: serde::de::DeserializeOwned,
+{
+    match note.clone().extract(N::METHOD) {
+        Ok(params) => Some(params),
+        Err(ExtractError::MethodMismatch(_)) => None,
+        Err(ExtractError::JsonError { method, error }) => {
+            warn!("invalid {method} notification: {error}");
+            None
+        }
+    }
+}
+
+impl Server {
+    fn run(&mut self) -> Result<()> {
+        while let Ok(msg) = self.connection.receiver.recv() {
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    self.handle_request(req)?;
+                }
+                Message::Notification(note) => self.handle_notification(note)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, req: Request) -> Result<()> {
+        debug!("request {}", req.method);
+        let resp = self.response(&req).unwrap_or_else(|err| err);
+        self.connection.sender.send(Message::Response(resp))?;
+        Ok(())
+    }
+
+    /// Build the response to a request, or an error response.
+    fn response(&self, req: &Request) -> std::result::Result<Response, Response> {
+        if let Some((id, params)) = cast::<HoverRequest>(req)? {
+            let pos = params.text_document_position_params;
+            let hover = self
+                .docs
+                .get(&pos.text_document.uri)
+                .and_then(|text| analysis::hover(text, pos.position))
+                .map(|value| Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value,
+                    }),
+                    range: None,
+                });
+            Ok(Response::new_ok(id, hover))
+        } else if let Some((id, params)) = cast::<Completion>(req)? {
+            let pos = params.text_document_position;
+            let items = self
+                .docs
+                .get(&pos.text_document.uri)
+                .map(|text| analysis::completions(text, pos.position))
+                .unwrap_or_default();
+            Ok(Response::new_ok(id, CompletionResponse::Array(items)))
+        } else if let Some((id, params)) = cast::<Formatting>(req)? {
+            let edits = self
+                .docs
+                .get(&params.text_document.uri)
+                .and_then(|text| analysis::format(text));
+            Ok(Response::new_ok(id, edits))
+        } else {
+            Err(Response::new_err(
+                req.id.clone(),
+                lsp_server::ErrorCode::MethodNotFound as i32,
+                format!("unsupported request {}", req.method),
+            ))
+        }
+    }
+
+    fn handle_notification(&mut self, note: Notification) -> Result<()> {
+        debug!("notification {}", note.method);
+        if let Some(params) = cast_notification::<DidOpenTextDocument>(&note) {
+            let doc = params.text_document;
+            self.update(doc.uri, doc.text, Some(doc.version))?;
+        } else if let Some(params) = cast_notification::<DidChangeTextDocument>(&note) {
+            // Full document sync, so the last change holds the whole text.
+            if let Some(change) = params.content_changes.into_iter().last() {
+                let doc = params.text_document;
+                self.update(doc.uri, change.text, Some(doc.version))?;
+            }
+        } else if let Some(params) = cast_notification::<DidCloseTextDocument>(&note) {
+            let uri = params.text_document.uri;
+            self.docs.remove(&uri);
+            self.publish(uri, Vec::new(), None)?;
+        }
+        Ok(())
+    }
+
+    /// Record new contents for a document and publish the resulting diagnostics.
+    fn update(&mut self, uri: Uri, text: String, version: Option<i32>) -> Result<()> {
+        let resolver = skreate::FileResolver::new(base_dir(&uri));
+        let diags = analysis::diagnostics(&uri, &text, &resolver);
+        self.docs.insert(uri.clone(), text);
+        self.publish(uri, diags, version)
+    }
+
+    fn publish(
+        &self,
+        uri: Uri,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+        version: Option<i32>,
+    ) -> Result<()> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        };
+        let note = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+        self.connection.sender.send(Message::Notification(note))?;
+        Ok(())
+    }
+}
+
+fn main() -> Result<()> {
+    // Log to stderr, as stdout carries the protocol.
+    env_logger::init();
+
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["[".to_string(), ",".to_string()]),
+            ..Default::default()
+        }),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+    info!("skreate language server initialized");
+
+    let mut server = Server {
+        connection,
+        docs: HashMap::new(),
+    };
+    server.run()?;
+    drop(server);
+    io_threads.join()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_params() {
+        let req = Request::new(
+            RequestId::from(1),
+            "textDocument/hover".to_string(),
+            serde_json::json!({"bad": true}),
+        );
+        let resp = cast::<HoverRequest>(&req).unwrap_err();
+        assert_eq!(
+            resp.error.map(|e| e.code),
+            Some(lsp_server::ErrorCode::InvalidParams as i32)
+        );
+        assert!(matches!(cast::<Completion>(&req), Ok(None)));
+
+        let note = Notification::new(
+            DidOpenTextDocument::METHOD.to_string(),
+            serde_json::json!({"bad": true}),
+        );
+        assert!(cast_notification::<DidOpenTextDocument>(&note).is_none());
+        assert!(cast_notification::<DidCloseTextDocument>(&note).is_none());
+    }
+}
@@ -4,8 +4,14 @@
 #![warn(missing_docs)]
 
 use log::{info, trace};
+use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
+thread_local! {
+    /// Contents of files available for inclusion.
+    static INCLUDES: RefCell<skreate::MemoryResolver> = RefCell::new(skreate::MemoryResolver::default());
+}
+
 // Rust functionality invoked from Javascript.
 
 /// Perform initialization. Safe to be invoked more than once.
@@ -19,11 +25,32 @@ pub fn initialize() {
     }
 }
 
+/// Make the given contents available for inclusion under the name `file`.
+#[wasm_bindgen]
+pub fn set_include(file: &str, contents: &str) {
+    trace!("In set_include('{file}')");
+    INCLUDES.with_borrow_mut(|includes| includes.insert(file, contents));
+}
+
+/// Remove all files available for inclusion.
+#[wasm_bindgen]
+pub fn clear_includes() {
+    trace!("In clear_includes()");
+    INCLUDES.with_borrow_mut(|includes| includes.clear());
+}
+
 /// Generate output.
 #[wasm_bindgen]
 pub fn generate(input: &str) -> Result<String, ParseError> {
     trace!("In generate('{input}')");
-    Ok(skreate::generate(input)?)
+    Ok(INCLUDES.with_borrow(|includes| skreate::generate_with_errors(input, includes))?)
+}
+
+/// Generate animated output, at the given tempo in beats per minute.
+#[wasm_bindgen]
+pub fn generate_animated(input: &str, tempo: u32) -> Result<String, ParseError> {
+    trace!("In generate_animated('{input}', {tempo})");
+    Ok(INCLUDES.with_borrow(|includes| skreate::generate_animated(input, includes, tempo))?)
 }
 
 /// Generated SVG and move positions.
@@ -35,6 +62,8 @@ pub struct GeneratedSvgPositions {
     positions: Vec<String>,
     /// List of associated timings.
     timings: Vec<usize>,
+    /// List of warnings about questionable input.
+    warnings: Vec<Warning>,
 }
 
 #[wasm_bindgen]
@@ -54,17 +83,24 @@ impl GeneratedSvgPositions {
     pub fn timings(&self) -> Vec<usize> {
         self.timings.clone()
     }
+    /// Retrieve the warnings.
+    #[wasm_bindgen(getter)]
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.warnings.clone()
+    }
 }
 
 /// Generate output with positions.
 #[wasm_bindgen]
 pub fn generate_with_positions(input: &str) -> Result<GeneratedSvgPositions, ParseError> {
     trace!("In generate_with_positions('{input}')");
-    let (svg, positions, timings) = skreate::generate_with_positions(input)?;
+    let (svg, positions, timings, warnings) =
+        INCLUDES.with_borrow(|includes| skreate::generate_with_positions(input, includes))?;
     Ok(GeneratedSvgPositions {
         svg,
         positions,
         timings,
+        warnings: warnings.into_iter().map(Warning::from).collect(),
     })
 }
 
@@ -82,9 +118,104 @@ pub fn minimize_vert(input: &str) -> Result<String, ParseError> {
     Ok(skreate::minimize_vert_url(input)?)
 }
 
+/// Warning about questionable input.  Equivalent of [`skreate::Warning`], mirrored here to allow conversion to
+/// Javascript.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Warning {
+    /// Row of input with warning.
+    pub row: usize,
+    /// Column of input with warning.
+    pub col: usize,
+    /// Count of chars covered by the warning.
+    pub count: usize,
+    /// Warning information.
+    msg: String,
+    /// Name of the included file that holds the warning; empty for the main input.
+    file: String,
+    /// Invocations of named sequences that led to the warning, innermost first.
+    calls: Vec<CallSite>,
+}
+
+#[wasm_bindgen]
+impl Warning {
+    /// Retrieve the message associated with the warning.
+    #[wasm_bindgen(getter)]
+    pub fn msg(&self) -> String {
+        self.msg.clone()
+    }
+
+    /// Retrieve the name of the included file that holds the warning, or an empty string for the main input.
+    #[wasm_bindgen(getter)]
+    pub fn file(&self) -> String {
+        self.file.clone()
+    }
+
+    /// Retrieve the invocations of named sequences that led to the warning, innermost first.
+    #[wasm_bindgen(getter)]
+    pub fn calls(&self) -> Vec<CallSite> {
+        self.calls.clone()
+    }
+}
+
+impl From<skreate::Warning> for Warning {
+    fn from(warning: skreate::Warning) -> Warning {
+        Warning {
+            row: warning.pos.row,
+            col: warning.pos.col,
+            count: warning.pos.count,
+            msg: warning.kind.to_string(),
+            file: warning.file.unwrap_or_default(),
+            calls: warning.calls.into_iter().map(CallSite::from).collect(),
+        }
+    }
+}
+
+/// Invocation of a named sequence.  Direct equivalent of [`skreate::CallSite`], mirrored here to allow conversion to
+/// Javascript.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct CallSite {
+    /// Row of input with the invocation.
+    pub row: usize,
+    /// Column of input with the invocation.
+    pub col: usize,
+    /// Name of the sequence.
+    name: String,
+    /// Name of the included file that holds the invocation; empty for the main input.
+    file: String,
+}
+
+#[wasm_bindgen]
+impl CallSite {
+    /// Retrieve the name of the sequence.
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Retrieve the name of the included file that holds the invocation, or an empty string for the main input.
+    #[wasm_bindgen(getter)]
+    pub fn file(&self) -> String {
+        self.file.clone()
+    }
+}
+
+impl From<skreate::CallSite> for CallSite {
+    fn from(call: skreate::CallSite) -> CallSite {
+        CallSite {
+            row: call.pos.row,
+            col: call.pos.col,
+            name: call.name,
+            file: call.file.unwrap_or_default(),
+        }
+    }
+}
+
 /// Error in parsing input.  Direct equivalent of [`skreate::ParseError`], mirrored here to allow conversion to
 /// Javascript.
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct ParseError {
     /// Row of input with error.
     pub row: usize,
@@ -92,6 +223,12 @@ pub struct ParseError {
     pub col: usize,
     /// Error information.
     msg: String,
+    /// Name of the included file that holds the error; empty for the main input.
+    file: String,
+    /// Invocations of named sequences that led to the error, innermost first.
+    calls: Vec<CallSite>,
+    /// Any further errors in the input, after this one.
+    more: Vec<ParseError>,
 }
 
 #[wasm_bindgen]
@@ -107,6 +244,24 @@ impl ParseError {
     pub fn set_msg(&mut self, msg: String) {
         self.msg = msg;
     }
+
+    /// Retrieve the name of the included file that holds the error, or an empty string for the main input.
+    #[wasm_bindgen(getter)]
+    pub fn file(&self) -> String {
+        self.file.clone()
+    }
+
+    /// Retrieve the invocations of named sequences that led to the error, innermost first.
+    #[wasm_bindgen(getter)]
+    pub fn calls(&self) -> Vec<CallSite> {
+        self.calls.clone()
+    }
+
+    /// Retrieve any further errors in the input, after this one.
+    #[wasm_bindgen(getter)]
+    pub fn more(&self) -> Vec<ParseError> {
+        self.more.clone()
+    }
 }
 
 impl From<skreate::ParseError> for ParseError {
@@ -115,6 +270,18 @@ impl From<skreate::ParseError> for ParseError {
             row: err.pos.row,
             col: err.pos.col,
             msg: err.msg,
+            file: err.file.unwrap_or_default(),
+            calls: err.calls.into_iter().map(CallSite::from).collect(),
+            more: Vec::new(),
         }
     }
 }
+
+impl From<Vec<skreate::ParseError>> for ParseError {
+    fn from(errs: Vec<skreate::ParseError>) -> ParseError {
+        let mut errs = errs.into_iter().map(ParseError::from);
+        let mut first = errs.next().expect("no errors");
+        first.more = errs.collect();
+        first
+    }
+}
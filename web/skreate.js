@@ -37,6 +37,9 @@ function set_svg_with_events(editor, div) {
   div.html(result.svg);
   var positions = result.positions;
   var timings = result.timings;
+  editor.getSession().setAnnotations(result.warnings.flatMap(function(w) {
+    return annotations_for(editor, "warning", w);
+  }));
 
   for (const text_pos of positions) {
     $( "[id^='"+text_pos+"']" ).each( function() {
@@ -142,9 +145,37 @@ export function setup_edit(div, get_value) {
   });
 }
 
+// Return an editor annotation for an error or warning.  Problems in included files are shown against the
+// `Include` of that file, or the first row if there isn't one in the editor.
+function annotation(editor, type, e) {
+  if (!e.file) {
+    return { type: type, row: e.row, column: e.col, text: e.msg };
+  }
+  var quoted = '"' + e.file + '"';
+  var lines = editor.getValue().split("\n");
+  var row = lines.findIndex((line) => line.includes("Include") && line.includes(quoted));
+  return {
+    type: type,
+    row: Math.max(row, 0),
+    column: 0,
+    text: e.file + ":" + (e.row + 1) + ":" + (e.col + 1) + ": " + e.msg
+  };
+}
+
+// Return the editor annotations for an error or warning.  Problems in a named sequence are shown against the move in
+// the definition, and also against each invocation that led to it.
+function annotations_for(editor, type, e) {
+  var calls = e.calls.map(function(c) {
+    var msg = 'in "' + c.name + '": ' + e.msg;
+    return annotation(editor, type, { row: c.row, col: c.col, file: c.file, msg: msg });
+  });
+  return [annotation(editor, type, e)].concat(calls);
+}
+
 function parse_text_pos(text_pos) {
-  // This also copes with suffixed versions (e.g. "r_0_c_0_5_n2").
-  var re = /r_(\d+)_c_(\d+)_(\d+)/;
+  // This also copes with suffixed versions (e.g. "r_0_c_0_5_n2"), but not with positions in included files
+  // (e.g. "f_steps-skate_r_0_c_0_5"), which don't correspond to the editor text.
+  var re = /^r_(\d+)_c_(\d+)_(\d+)/;
   var m = text_pos.match(re);
   if (!m) {
     return null;
@@ -208,8 +239,8 @@ function highlight_text(editor, text_pos, enabled) {
     marked_text_position = null;
   }
 
-  if (enabled) {
-    var pos = parse_text_pos(text_pos);
+  var pos = enabled ? parse_text_pos(text_pos) : null;
+  if (pos) {
     var range = new AceRange(pos.row, pos.col, pos.row, pos.endcol);
     current_text_marker = editor.getSession().addMarker(range, "ace_selected_word", "text");
     console.log("set text marker at " + text_pos + " == " + range + " => marker_id=" + current_text_marker);
@@ -295,18 +326,18 @@ function setup_editor_internal(div, autofocus, text, include_io) {
         highlight_elt(to_highlight);
       });
     } catch(err) {
-      var annotation = {
+      var annotations = [{
         type: "error", // also warning and information
         column: 0,
         row: 0,
         text: err.message
-      };
+      }];
       if (err instanceof ParseError) {
-        annotation.row    = err.row;
-        annotation.column = err.col;
-        annotation.text   = err.msg;
+        annotations = [err].concat(err.more).flatMap(function(e) {
+          return annotations_for(editor, "error", e);
+        });
       }
-      editor.getSession().setAnnotations([annotation]);
+      editor.getSession().setAnnotations(annotations);
     }
   }
 